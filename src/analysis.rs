@@ -0,0 +1,25 @@
+use std::{io, process::Command};
+
+// the lichess analysis board set up with the position
+pub fn get_lichess_url(fen: &str) -> String {
+    format!("https://lichess.org/analysis/standard/{}", fen.replace(' ', "_"))
+}
+
+// open the url with the default browser of the system
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        // the empty argument is the title of the window start would open
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    // the browser keeps running on its own, so the child isn't waited for
+    command.arg(url).spawn()?;
+
+    Ok(())
+}
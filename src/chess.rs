@@ -1,20 +1,42 @@
-use std::iter;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 use ggez::{
     glam::{vec2, Vec2},
     graphics::{self, TextAlign, TextLayout},
+    input::keyboard::KeyCode,
     *,
 };
 
-use crate::{game::*, move_calculator, piece::*, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::{
+    fen,
+    game::*,
+    game_event::*,
+    middlegame,
+    move_calculator::{self, Move, MoveKind},
+    pgn,
+    piece::*,
+    snapshot::PositionSnapshot,
+    square::Square,
+    ui::Hud,
+    WINDOW_HEIGHT, WINDOW_WIDTH,
+};
 
 pub const BOARD_WIDTH: usize = 8;
 pub const BOARD_HEIGHT: usize = 8;
 const CELL_SIZE: f32 = 80.0;
 
+// how long the reason for a rejected move stays on the screen
+const ILLEGAL_MOVE_REASON_TIME: Duration = Duration::from_secs(2);
+
+// how many of the last moves the move list shows beside the board
+const MOVE_LIST_PLIES: usize = 30;
+
 pub type Board<T> = [[T; BOARD_HEIGHT]; BOARD_WIDTH];
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 enum TurnState {
     Normal,
     Promotion(Promoter),
@@ -23,9 +45,9 @@ enum TurnState {
     Stalemate,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 struct Promoter {
-    cell: (usize, usize),
+    cell: Square,
     color: PieceColor,
 
     position: Vec2,
@@ -39,7 +61,7 @@ impl Promoter {
         PieceType::Bishop,
     ];
 
-    fn new(position: Vec2, cell: (usize, usize), color: PieceColor) -> Self {
+    fn new(position: Vec2, cell: Square, color: PieceColor) -> Self {
         Promoter {
             cell,
             color,
@@ -59,7 +81,7 @@ impl Promoter {
 
         let (x, y) = (cell.x as i32, cell.y as i32);
 
-        let is_in_bound = x >= 0 && x < 4 && y == 0;
+        let is_in_bound = (0..4).contains(&x) && y == 0;
 
         if !is_in_bound {
             return None;
@@ -68,14 +90,7 @@ impl Promoter {
         Some(Self::PROMOTION_PIECES[x as usize])
     }
 
-    fn draw(
-        &self,
-        ctx: &mut Context,
-        canvas: &mut graphics::Canvas,
-        assets: &mut Assets,
-    ) -> GameResult {
-        let sprite_original_size = 460.0;
-
+    fn draw(&self, canvas: &mut graphics::Canvas, assets: &Assets) {
         for (x, piece_type) in Self::PROMOTION_PIECES.iter().enumerate() {
             let piece = Piece::new(*piece_type, self.color);
 
@@ -83,21 +98,12 @@ impl Promoter {
             let cell_pos = self.position + vec2(CELL_SIZE * x as f32, 0.);
             let cell_pos_centered = cell_pos + vec2(CELL_SIZE / 2.0, CELL_SIZE / 2.0);
 
-            let image = piece.get_image(ctx, assets);
-            let drawparams = graphics::DrawParam::new()
-                .dest(cell_pos_centered)
-                .offset([0.5, 0.5]) // offset so that the sprite center and the drawing position align
-                .scale([
-                    CELL_SIZE / sprite_original_size,
-                    CELL_SIZE / sprite_original_size,
-                ]);
-            canvas.draw(image, drawparams);
+            piece.draw(canvas, assets, cell_pos_centered, CELL_SIZE, graphics::Color::WHITE);
         }
-
-        Ok(())
     }
 }
 
+#[derive(Clone)]
 struct TurnInfo {
     state: TurnState,
     color: PieceColor,
@@ -112,102 +118,333 @@ impl TurnInfo {
     }
 }
 
+// what's known about the current position, computed once per move instead of every frame
+#[derive(Clone)]
+struct PositionCache {
+    white_attack_counts: Board<u32>,
+    black_attack_counts: Board<u32>,
+    // lines along which the king of the side to move is attacked, empty if it's not in check
+    check_rays: Vec<Vec<Square>>,
+}
+
+impl PositionCache {
+    fn new(board: &Board<Option<Piece>>, color: PieceColor) -> Self {
+        let king = Square::all().find(|&square| {
+            board[square].is_some_and(|piece| {
+                piece.get_color() == color && piece.get_piece_type() == PieceType::King
+            })
+        });
+
+        PositionCache {
+            white_attack_counts: move_calculator::get_attack_counts(board, PieceColor::White),
+            black_attack_counts: move_calculator::get_attack_counts(board, PieceColor::Black),
+            check_rays: king.map_or_else(Vec::new, |king| {
+                move_calculator::get_check_rays(board, king)
+            }),
+        }
+    }
+}
+
+// a move held back until the player confirms it by clicking its destination again
+#[derive(Clone)]
+struct PendingMove {
+    from: Square,
+    to: Square,
+
+    // set by assist mode when the move looks like a blunder
+    threat: Option<String>,
+}
+
+// what a move changed on the board, enough to take it back
+#[derive(Clone, Copy)]
+struct Undo {
+    from: Square,
+    to: Square,
+    // the piece as it was before moving
+    piece: Piece,
+    captured: Option<(Square, Piece)>,
+    // where the rook of a castling move was, where it went, and the rook before moving
+    castling_rook: Option<(Square, Square, Piece)>,
+    en_passant_target: Option<Square>,
+}
+
+// special rules that get explained the first time they come up
+#[derive(Copy, Clone, PartialEq)]
+enum Rule {
+    EnPassant,
+    Castling,
+    Promotion,
+    Stalemate,
+}
+
+impl Rule {
+    fn get_explanation(&self) -> &'static str {
+        match self {
+            Rule::EnPassant => {
+                "En passant\n\nA pawn that has just moved two squares can be captured \
+                 by an enemy pawn beside it, as if it had moved only one. \
+                 This is only allowed right away."
+            }
+            Rule::Castling => {
+                "Castling\n\nThe king moves two squares towards a rook, \
+                 and the rook jumps to the square the king crossed. \
+                 Neither may have moved, the squares between them must be empty, \
+                 and the king may not castle out of, through, or into check."
+            }
+            Rule::Promotion => {
+                "Promotion\n\nA pawn reaching the last rank is exchanged \
+                 for a queen, rook, bishop or knight of the same color."
+            }
+            Rule::Stalemate => {
+                "Stalemate\n\nThe side to move has no legal moves but is not in check. \
+                 The game is a draw."
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RuleTip {
+    rule: Rule,
+
+    // cells highlighted on the board while the tip is shown
+    cells: Vec<Square>,
+}
+
+// how much eye candy is drawn, so the game runs well on low-end machines and on battery
+#[derive(Copy, Clone, PartialEq)]
+pub enum GraphicsQuality {
+    // nothing moves, and the board is only redrawn when it changes
+    Off,
+    // animations at a reduced frame rate, without effects
+    Low,
+    // animations every frame, and shadows
+    High,
+}
+
+impl GraphicsQuality {
+    fn get_next(self) -> Self {
+        match self {
+            GraphicsQuality::Off => GraphicsQuality::Low,
+            GraphicsQuality::Low => GraphicsQuality::High,
+            GraphicsQuality::High => GraphicsQuality::Off,
+        }
+    }
+
+    // how long an animated board waits between redraws, None to redraw every frame
+    pub fn get_frame_time(self) -> Option<Duration> {
+        match self {
+            // only timed messages change, and they can wait
+            GraphicsQuality::Off => Some(Duration::from_millis(500)),
+            GraphicsQuality::Low => Some(Duration::from_millis(66)),
+            GraphicsQuality::High => None,
+        }
+    }
+
+    fn get_label(self) -> &'static str {
+        match self {
+            GraphicsQuality::Off => "Graphics off (G)",
+            GraphicsQuality::Low => "Low graphics (G)",
+            GraphicsQuality::High => "High graphics (G)",
+        }
+    }
+}
+
+// why a move couldn't be applied to the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveError {
+    EmptyOrigin(Square),
+    // the piece on the origin belongs to the side not to move
+    WrongColor(Square),
+    Illegal { from: Square, to: Square },
+    // a castling king has no rook to take along
+    MissingRook(Square),
+    // the last move promoted a pawn, and its piece has yet to be chosen
+    PendingPromotion,
+}
+
+#[derive(Clone)]
 pub struct Chess {
     // fields for game logic
     board: Board<Option<Piece>>,
-    selected_cell: Option<(usize, usize)>,
+    selected_cell: Option<Square>,
+
+    // the cell a pawn skipped with a double step on the last move,
+    // where it can be captured en passant for this turn only
+    en_passant_target: Option<Square>,
 
-    legal_moves: Board<Board<bool>>,
-    is_movable: Board<bool>,
+    // legal moves of the current turn, grouped by origin cell
+    legal_moves: Vec<Move>,
+    // legal_moves[start..end] are the moves of the piece on each cell
+    legal_move_ranges: Board<(usize, usize)>,
 
     turn_info: TurnInfo,
     change_turn: bool,
+    // whether anything drawn has changed since the last take_changed
+    is_changed: bool,
+
+    // refreshed whenever the board changes
+    position_cache: PositionCache,
 
     promoter_position: Vec2,
 
+    assist_mode: bool,
+    confirm_moves: bool,
+    pending_move: Option<PendingMove>,
+
+    auto_queen: bool,
+
+    // for fast games: always queen, no confirmations, popups or animations
+    low_latency: bool,
+
+    show_rule_tips: bool,
+    seen_rules: Vec<Rule>,
+    rule_tip: Option<RuleTip>,
+
+    // why the last attempted move was rejected, and when
+    illegal_move_reason: Option<(String, Instant)>,
+
+    // how many times each cell has been occupied or attacked after a move
+    activity: Board<u32>,
+
+    events: Vec<GameEvent>,
+
+    // the moves played since the game started, with the piece each promotion chose
+    played_moves: Vec<(Move, Option<PieceType>)>,
+    // a game set up from a FEN may start with black
+    starting_color: PieceColor,
+
     // fields for drawing
     position: Vec2,
+    hovered_cell: Option<Square>,
+    // where the selected piece is drawn while it's being dragged
+    drag_position: Option<Vec2>,
+    show_attack_counts: bool,
+    show_heat_map: bool,
+    // movable pieces pulse instead of getting a tinted cell
+    pulse_movable: bool,
+    graphics_quality: GraphicsQuality,
+    // while in check, show the checking lines and dim the pieces that can't help
+    explain_check: bool,
+    // the move list is written in iccf numeric notation instead of coordinates
+    iccf_notation: bool,
+
+    // hides every text and overlay, leaving the board, the pieces and the popups that need input
+    zen_mode: bool,
 }
 
 impl Chess {
     pub fn new(position: Vec2) -> Self {
+        let board = [[None; BOARD_HEIGHT]; BOARD_WIDTH];
+
         Chess {
-            board: [[None; BOARD_HEIGHT]; BOARD_WIDTH],
+            board,
             selected_cell: None,
 
-            legal_moves: [[[[false; BOARD_HEIGHT]; BOARD_WIDTH]; BOARD_HEIGHT]; BOARD_WIDTH],
-            is_movable: [[false; BOARD_HEIGHT]; BOARD_WIDTH],
+            legal_moves: Vec::new(),
+            legal_move_ranges: [[(0, 0); BOARD_HEIGHT]; BOARD_WIDTH],
+
+            en_passant_target: None,
 
             turn_info: TurnInfo::new(),
             change_turn: false,
+            is_changed: true,
+
+            position_cache: PositionCache::new(&board, PieceColor::White),
 
             promoter_position: vec2(WINDOW_WIDTH / 2.0 - 2.0 * CELL_SIZE, WINDOW_HEIGHT - CELL_SIZE),
 
+            assist_mode: false,
+            confirm_moves: false,
+            pending_move: None,
+
+            auto_queen: false,
+
+            low_latency: false,
+
+            show_rule_tips: false,
+            seen_rules: Vec::new(),
+            rule_tip: None,
+
+            illegal_move_reason: None,
+
+            activity: [[0; BOARD_HEIGHT]; BOARD_WIDTH],
+
+            events: Vec::new(),
+
+            played_moves: Vec::new(),
+            starting_color: PieceColor::White,
+
             position,
+            hovered_cell: None,
+            drag_position: None,
+            show_attack_counts: false,
+            show_heat_map: false,
+            pulse_movable: false,
+            graphics_quality: GraphicsQuality::High,
+            explain_check: false,
+            iccf_notation: false,
+
+            zen_mode: false,
         }
     }
 
-    pub fn init(mut self) -> Self {
-        let setup: &[u8] = "rnbqkbnr\
-                            pppppppp\
-                            --------\
-                            --------\
-                            --------\
-                            --------\
-                            PPPPPPPP\
-                            RNBQKBNR"
-            .as_bytes();
-
-        for (ind, curr) in setup.iter().enumerate() {
-            let curr = *curr as char;
-
-            let piece_type = match curr {
-                '-' => continue,
-                'r' | 'R' => PieceType::Rook,
-                'n' | 'N' => PieceType::Knight,
-                'b' | 'B' => PieceType::Bishop,
-                'q' | 'Q' => PieceType::Queen,
-                'k' | 'K' => PieceType::King,
-                'p' | 'P' => PieceType::Pawn { en_passant: false },
-                other => panic!("invalid board configuration: {other}"),
-            };
+    pub fn init(self) -> Self {
+        self.init_from_fen(fen::START_FEN)
+            .expect("the starting position should be valid")
+    }
 
-            let color = if curr.is_lowercase() {
-                PieceColor::Black
-            } else {
-                PieceColor::White
-            };
+    // set up the position of a FEN string on a fresh game, None if it's not a legal position
+    pub fn init_from_fen(mut self, fen: &str) -> Option<Self> {
+        let (board, color, en_passant_target) = fen::parse_fen(fen)?;
 
-            let (x, y) = Chess::to_index_2d(ind);
-            self.board[x][y] = Some(Piece::new(piece_type, color));
-        }
+        self.board = board;
+        self.turn_info.color = color;
+        self.starting_color = color;
+        self.en_passant_target = en_passant_target;
+
+        self.refresh_position_cache();
 
-        self.print();
+        if !self.position_cache.check_rays.is_empty() {
+            self.turn_info.state = TurnState::Check;
+        }
 
         self.compute_moves();
 
-        self
+        Some(self)
     }
 
-    fn print(&self) {
-        for y in 0..BOARD_HEIGHT {
-            for x in 0..BOARD_WIDTH {
-                print!(
-                    "{}",
-                    if let Some(piece) = &self.board[x][y] {
-                        piece.to_string()
-                    } else {
-                        '_'.to_string()
-                    }
-                );
-            }
-            println!();
-        }
+    // throw the game in progress away and start over from the given position,
+    // keeping the player's settings. returns false if it's not a legal position.
+    pub fn restart_from_fen(&mut self, fen: &str) -> bool {
+        let Some(fresh) = Chess::new(self.position).init_from_fen(fen) else { return false };
+
+        let mut events = std::mem::take(&mut self.events);
+        events.push(GameEvent::GameStarted { fen: fen.to_string() });
+
+        *self = Chess {
+            assist_mode: self.assist_mode,
+            confirm_moves: self.confirm_moves,
+            auto_queen: self.auto_queen,
+            low_latency: self.low_latency,
+            show_rule_tips: self.show_rule_tips,
+            seen_rules: std::mem::take(&mut self.seen_rules),
+            events,
+            show_attack_counts: self.show_attack_counts,
+            show_heat_map: self.show_heat_map,
+            pulse_movable: self.pulse_movable,
+            graphics_quality: self.graphics_quality,
+            explain_check: self.explain_check,
+            iccf_notation: self.iccf_notation,
+            zen_mode: self.zen_mode,
+            ..fresh
+        };
+
+        true
     }
 
     fn compute_moves(&mut self) {
         self.compute_each_legal_moves();
-        self.compute_is_movable();
 
         // if no legal moves for all pieces
         //      if inCheck
@@ -215,7 +452,7 @@ impl Chess {
         //      else
         //          then stalemate -> draw
 
-        if !self.is_movable.iter().any(|row| row.contains(&true)) {
+        if self.legal_moves.is_empty() {
             self.turn_info.state = if self.turn_info.state == TurnState::Check {
                 TurnState::Checkmate
             } else {
@@ -224,400 +461,1263 @@ impl Chess {
         }
     }
 
-    pub fn update(&mut self, mouse: &Mouse) {
+    pub fn update(&mut self, mouse: &Mouse, keyboard: &Keyboard) {
         if self.change_turn {
-            self.change_turn();
+            self.finish_turn();
+            self.try_show_rule_tip();
         }
 
-        if let TurnState::Promotion(promoter) = &self.turn_info.state {
-            let cell = promoter.cell;
+        let is_reason_expired = self
+            .illegal_move_reason
+            .as_ref()
+            .is_some_and(|(_, shown_at)| shown_at.elapsed() >= ILLEGAL_MOVE_REASON_TIME);
 
-            let Some(chosen) = (*promoter).choose_promotion(mouse) else {
-                return;
-            };
+        if is_reason_expired {
+            self.illegal_move_reason = None;
+            self.is_changed = true;
+        }
 
-            self.board[cell.0][cell.1]
-                .as_mut()
-                .expect("the given cell should contain a pawn")
-                .promote(chosen);
+        if keyboard.is_key_pressed(KeyCode::A) {
+            self.assist_mode = !self.assist_mode;
+            self.pending_move = None;
+        }
 
-            self.change_turn = true;
+        if keyboard.is_key_pressed(KeyCode::M) {
+            self.confirm_moves = !self.confirm_moves;
+            self.pending_move = None;
+        }
 
-            return;
+        if keyboard.is_key_pressed(KeyCode::C) {
+            self.show_attack_counts = !self.show_attack_counts;
         }
-        
-        if mouse.is_mouse_pressed(event::MouseButton::Left) {
-            let cell = self.try_select_cell(mouse);
-
-            if let Some((cell_x, cell_y)) = cell {
-                let is_movable = self.selected_cell.is_some_and(|(piece_x, piece_y)| {
-                    self.legal_moves[piece_x][piece_y][cell_x][cell_y]
-                });
-
-                if is_movable {
-                    // when there's a selected piece and newly-selected cell is one of it's possible moves
-                    // move the piece and change the turn
-                    self.move_piece(self.selected_cell.unwrap(), (cell_x, cell_y));
-                    self.post_move_update();
-                } else {
-                    // select new piece on this cell
-                    self.selected_cell = cell;
-                }
-            } else {
-                self.selected_cell = None;
-            }
+
+        if keyboard.is_key_pressed(KeyCode::H) {
+            self.show_heat_map = !self.show_heat_map;
         }
-    }
 
-    pub fn to_index_1d((x, y): (usize, usize)) -> usize {
-        y * BOARD_WIDTH + x
-    }
+        if keyboard.is_key_pressed(KeyCode::P) {
+            self.pulse_movable = !self.pulse_movable;
+        }
 
-    pub fn to_index_2d(ind: usize) -> (usize, usize) {
-        (ind % BOARD_WIDTH, ind / BOARD_WIDTH)
-    }
+        if keyboard.is_key_pressed(KeyCode::G) {
+            self.graphics_quality = self.graphics_quality.get_next();
+        }
 
-    pub fn is_position_in_bound((x, y): (i32, i32)) -> bool {
-        x >= 0 && x < BOARD_WIDTH as i32 && y >= 0 && y < BOARD_HEIGHT as i32
-    }
+        if keyboard.is_key_pressed(KeyCode::E) {
+            self.explain_check = !self.explain_check;
+        }
 
-    pub fn is_empty_on(board_state: &Board<Option<Piece>>, (x, y): (usize, usize)) -> bool {
-        board_state[x][y].is_none()
-    }
+        if keyboard.is_key_pressed(KeyCode::Z) {
+            self.zen_mode = !self.zen_mode;
+        }
 
-    pub fn is_color_on(
-        board_state: &Board<Option<Piece>>,
-        (x, y): (usize, usize),
-        color: PieceColor,
-    ) -> bool {
-        let Some(piece) = board_state[x][y] else { return false; };
-        piece.get_color() == color
-    }
+        if keyboard.is_key_pressed(KeyCode::V) {
+            self.iccf_notation = !self.iccf_notation;
+        }
 
-    fn try_select_cell(&self, mouse: &Mouse) -> Option<(usize, usize)> {
-        let m_pos = mouse.get_mouse();
-        let cell = ((m_pos - self.position) / CELL_SIZE).floor();
+        if keyboard.is_key_pressed(KeyCode::Q) {
+            self.auto_queen = !self.auto_queen;
+        }
 
-        let (x, y) = (cell.x as i32, cell.y as i32);
+        if keyboard.is_key_pressed(KeyCode::L) {
+            self.low_latency = !self.low_latency;
+            self.pending_move = None;
+            self.rule_tip = None;
+        }
 
-        if Chess::is_position_in_bound((x, y)) {
-            return Some((x as usize, y as usize));
+        if keyboard.is_key_pressed(KeyCode::T) {
+            self.show_rule_tips = !self.show_rule_tips;
+            self.rule_tip = None;
         }
 
-        None
-    }
+        if keyboard.is_key_pressed(KeyCode::R) {
+            // tests/middlegame.rs keeps the list legal, but a broken entry shouldn't end the game
+            if !self.restart_from_fen(middlegame::pick_random()) {
+                let reason = "That middlegame isn't a legal position".to_string();
+                self.illegal_move_reason = Some((reason, Instant::now()));
+            }
 
-    fn move_piece(&mut self, from: (usize, usize), to: (usize, usize)) {
-        let mut src = self.board[from.0][from.1];
+            return;
+        }
 
-        let Some(src_piece) = &mut src else { panic!("{:?} should contain a piece", from) };
+        // holding ctrl while moving the pawn brings the promoter back for underpromotion
+        let auto_queen = (self.auto_queen || self.low_latency) && !keyboard.is_ctrl_down();
 
-        src_piece.set_has_moved(true);
+        self.hovered_cell = self.try_select_cell(mouse);
 
-        let mut attacking_position = to;
+        if self.rule_tip.is_some() {
+            // the tip blocks the board until it is dismissed
+            if mouse.is_mouse_pressed(event::MouseButton::Left) {
+                self.rule_tip = None;
+            }
 
-        // handle special moves
-        self.move_en_passant(from, to, src_piece, &mut attacking_position);
-        self.move_castling(from, to, src_piece);
+            return;
+        }
 
-        self.board[from.0][from.1] = None;
-        self.board[attacking_position.0][attacking_position.1] = None;
-        self.board[to.0][to.1] = src;
-    }
+        if let TurnState::Promotion(promoter) = &self.turn_info.state {
+            let cell = promoter.cell;
 
-    fn move_castling(&mut self, from: (usize, usize), to: (usize, usize), src_piece: &mut Piece) {
-        let is_castling =
-            (src_piece.get_piece_type() == PieceType::King) && (from.0.abs_diff(to.0) == 2);
+            let Some(chosen) = (*promoter).choose_promotion(mouse) else {
+                return;
+            };
+
+            self.promote(cell, chosen);
 
-        if !is_castling {
             return;
-        };
+        }
+
+        let is_dragging = mouse.is_dragging(event::MouseButton::Left);
 
-        let rook_x = if to.0 > from.0 { BOARD_WIDTH - 1 } else { 0 };
-        let rook_new_x = if to.0 > from.0 { to.0 - 1 } else { to.0 + 1 };
+        // the selected piece follows the mouse while it's dragged from its cell
+        let drag_cell = mouse
+            .get_drag_start(event::MouseButton::Left)
+            .and_then(|start| self.get_cell_at(start));
 
-        let mut rook = self.board[rook_x][from.1].expect("cell should not be empty");
-        self.board[rook_x][from.1] = None;
+        self.drag_position = (is_dragging
+            && mouse.is_mouse_down(event::MouseButton::Left)
+            && drag_cell.is_some()
+            && drag_cell == self.selected_cell)
+            .then(|| mouse.get_mouse());
 
-        rook.set_has_moved(true);
-        self.board[rook_new_x][from.1] = Some(rook);
+        if mouse.is_mouse_pressed(event::MouseButton::Left) {
+            self.click_cell(self.try_select_cell(mouse), auto_queen);
+        } else if mouse.is_mouse_released(event::MouseButton::Left)
+            && is_dragging
+            && drag_cell == self.selected_cell
+            && self.pending_move.is_none()
+        {
+            let drop_cell = self.try_select_cell(mouse);
+
+            match drop_cell {
+                // dropping the piece on one of its moves plays it like a second click
+                Some(to) if self.get_selected_move(to).is_some() => {
+                    self.click_cell(drop_cell, auto_queen);
+                }
+                // dropping it off the board cancels the selection
+                None => self.selected_cell = None,
+                // anywhere else it goes back to its cell
+                Some(to) => {
+                    self.try_explain_illegal_move(to);
+                }
+            }
+        }
     }
 
-    fn move_en_passant(
+    // play a legal move of the current turn without any input, e.g. when recovering a game.
+    // without promote_to, a promoting pawn waits for the player's choice as usual.
+    // the board is left untouched if the move can't be played.
+    pub fn apply_move(
         &mut self,
-        from: (usize, usize),
-        to: (usize, usize),
-        src_piece: &mut Piece,
-        attacking_position: &mut (usize, usize),
-    ) {
-        let PieceType::Pawn { en_passant } = src_piece.get_piece_type_mut() else { return };
+        from: Square,
+        to: Square,
+        promote_to: Option<PieceType>,
+    ) -> Result<(), MoveError> {
+        if let TurnState::Promotion(..) = self.turn_info.state {
+            return Err(MoveError::PendingPromotion);
+        }
 
-        if from.0 != to.0 {
-            // if the pawn moves diagonally, check for en passant attack
-            let en_passant_target = self.board[to.0][from.1];
+        let Some(piece) = self.board[from] else { return Err(MoveError::EmptyOrigin(from)) };
 
-            if en_passant_target.is_some_and(|enemy| {
-                enemy.get_color() != src_piece.get_color()
-                    && matches!(enemy.get_piece_type(), PieceType::Pawn { en_passant: true })
-            }) {
-                *attacking_position = (to.0, from.1);
-            }
-        } else if from.1.abs_diff(to.1) == 2 {
-            // if the pawn moves 2 cells vertically
-            //      then it's its first move
-            //           enable en passant for the next turn
-            *en_passant = true;
+        if piece.get_color() != self.turn_info.color {
+            return Err(MoveError::WrongColor(from));
         }
-    }
 
-    // compute and populate each piece's legal moves
-    fn compute_each_legal_moves(&mut self) {
-        // reset previous legal moves
-        self.legal_moves = [[[[false; BOARD_HEIGHT]; BOARD_WIDTH]; BOARD_HEIGHT]; BOARD_WIDTH];
+        if self.find_legal_move(from, to).is_none() {
+            return Err(MoveError::Illegal { from, to });
+        }
 
-        // iterate each piece of current turn and compute its legal moves
-        for x in 0..BOARD_WIDTH {
-            for y in 0..BOARD_HEIGHT {
-                if !Chess::is_color_on(&self.board, (x, y), self.turn_info.color) {
-                    continue;
-                }
+        self.play_move(from, to, false)?;
 
-                move_calculator::get_pseudo_legal_moves(
-                    &self.board,
-                    (x, y),
-                    &mut self.legal_moves[x][y],
-                );
+        if let TurnState::Promotion(promoter) = &self.turn_info.state {
+            let Some(promote_to) = promote_to else { return Ok(()) };
 
-                self.eliminate_illegal_moves((x, y));
-            }
+            self.promote(promoter.cell, promote_to);
         }
-    }
-
-    fn eliminate_illegal_moves(&mut self, from: (usize, usize)) {
-        let mut moves = self.legal_moves[from.0][from.1];
-        let board_saved = self.board;
 
-        for to_x in 0..BOARD_WIDTH {
-            for to_y in 0..BOARD_HEIGHT {
-                let to = (to_x, to_y);
+        self.finish_turn();
 
-                if !moves[to.0][to.1] {
-                    continue;
-                }
+        Ok(())
+    }
 
-                // temporarily move the piece to the destination
-                self.move_piece(from, to);
+    fn promote(&mut self, cell: Square, promote_to: PieceType) {
+        let pawn = self.board[cell]
+            .as_mut()
+            .expect("the given cell should contain a pawn");
 
-                if self.is_in_check(self.turn_info.color) {
-                    moves[to.0][to.1] = false;
-                }
+        pawn.promote(promote_to);
 
-                // recover the original state
-                self.board = board_saved;
-            }
+        if let Some((_, piece_type)) = self.played_moves.last_mut() {
+            *piece_type = Some(promote_to);
         }
 
-        // special case: check illegal moves for castling
-        self.eliminate_castling_illegal_moves(from, &mut moves, &board_saved);
+        self.events.push(GameEvent::Promotion {
+            color: pawn.get_color(),
+            cell,
+            promote_to,
+        });
 
-        self.legal_moves[from.0][from.1] = moves;
+        self.change_turn = true;
     }
 
-    fn eliminate_castling_illegal_moves(
-        &mut self,
-        from: (usize, usize),
-        moves: &mut Board<bool>,
-        board_saved: &Board<Option<Piece>>,
-    ) {
-        // legal castling condition:
-        // A player may not castle out of, through, or into check.
+    fn click_cell(&mut self, cell: Option<Square>, auto_queen: bool) {
+        self.illegal_move_reason = None;
+
+        if let Some(pending) = self.pending_move.take() {
+            // clicking the destination again confirms the move, anything else cancels it
+            if cell == Some(pending.to) {
+                self.play_clicked_move(pending.from, pending.to, auto_queen);
+            }
 
-        if self.board[from.0][from.1].unwrap().get_piece_type() != PieceType::King {
             return;
         }
 
-        for x_dir in [-1, 1] {
-            let castling_dst = (from.0 as i32 + x_dir * 2, from.1 as i32);
+        let Some(to) = cell else {
+            self.selected_cell = None;
+            return;
+        };
 
-            if !Chess::is_position_in_bound(castling_dst) {
-                continue;
-            };
+        let legal_move = self
+            .selected_cell
+            .and_then(|from| self.find_legal_move(from, to));
 
-            let castling = &mut moves[castling_dst.0 as usize][castling_dst.1 as usize];
+        let Some(legal_move) = legal_move else {
+            if !self.try_explain_illegal_move(to) {
+                // select new piece on this cell
+                self.selected_cell = cell;
+            }
 
-            if !*castling {
-                continue;
-            };
+            return;
+        };
 
-            // move the king one cell at a time towards the castling destination
-            // and see if it's in check
-            for x in 0..2 {
-                let to = ((from.0 as i32 + x_dir * x) as usize, from.1);
+        // when there's a selected piece and newly-selected cell is one of it's possible moves
+        // move the piece and change the turn
+        let from = legal_move.from;
 
-                self.move_piece(from, to);
+        let threat = if self.assist_mode && !self.low_latency {
+            self.find_blunder(&legal_move)
+        } else {
+            None
+        };
 
-                if self.is_in_check(self.turn_info.color) {
-                    *castling = false;
-                }
+        if (self.confirm_moves && !self.low_latency) || threat.is_some() {
+            self.pending_move = Some(PendingMove { from, to, threat });
+            return;
+        }
 
-                self.board = *board_saved;
-            }
+        self.play_clicked_move(from, to, auto_queen);
+    }
+
+    // a move that can't be played is shown like the reason for an illegal one
+    fn play_clicked_move(&mut self, from: Square, to: Square, auto_queen: bool) {
+        if let Err(err) = self.play_move(from, to, auto_queen) {
+            self.illegal_move_reason = Some((err.to_string(), Instant::now()));
         }
     }
 
-    fn is_in_check(&self, color: PieceColor) -> bool {
-        let mut kings_position: Option<(usize, usize)> = None;
+    // tell why the selected piece can't go to the given cell, keeping it selected.
+    // returns false if there's nothing to explain, e.g. another own piece was picked.
+    fn try_explain_illegal_move(&mut self, to: Square) -> bool {
+        let color = self.turn_info.color;
 
-        // find king of the given color
-        for x in 0..BOARD_WIDTH {
-            for y in 0..BOARD_HEIGHT {
-                if self.board[x][y].is_some_and(|piece| {
-                    piece.get_color() == color && piece.get_piece_type() == PieceType::King
-                }) {
-                    kings_position = Some((x, y));
-                    break;
-                }
-            }
-        }
+        let Some(from) = self.selected_cell else { return false };
 
-        let kings_position = kings_position.expect("king not found in the board");
-        let enemy_color = color.get_enemy_color();
+        if from == to
+            || !Chess::is_color_on(&self.board, from, color)
+            || Chess::is_color_on(&self.board, to, color)
+        {
+            return false;
+        }
 
-        let enemy_attacks = move_calculator::get_all_attacks(&self.board, enemy_color);
+        let reason = self.explain_illegal_move(from, to);
+        self.illegal_move_reason = Some((reason, Instant::now()));
 
-        enemy_attacks[kings_position.0][kings_position.1]
+        true
     }
 
-    fn post_move_update(&mut self) {
-        let mut delay_turn = false;
-
-        for x in 0..BOARD_WIDTH {
-            for y in 0..BOARD_HEIGHT {
-                let Some(piece) = &mut self.board[x][y] else { continue };
+    fn explain_illegal_move(&self, from: Square, to: Square) -> String {
+        let piece = self.board[from].expect("the origin of a move should contain a piece");
+        let name = piece.get_piece_type().get_name();
 
-                // update en passant
-                match (piece.get_color(), piece.get_piece_type_mut()) {
-                    (color, PieceType::Pawn { en_passant })
-                        if color == self.turn_info.color.get_enemy_color() =>
-                    {
-                        *en_passant = false;
-                    }
-                    _ => {}
-                }
+        let mut pseudo_legal = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+        move_calculator::get_pseudo_legal_moves(
+            &self.board,
+            from,
+            self.en_passant_target,
+            &mut pseudo_legal,
+        );
 
-                // update promotion
-                let promotionable_row = match piece.get_color() {
-                    PieceColor::White => 0,
-                    PieceColor::Black => BOARD_HEIGHT - 1,
-                };
+        if !pseudo_legal[to] {
+            // with the rest of the board cleared, could it get there?
+            let mut lone_board = [[None; BOARD_HEIGHT]; BOARD_WIDTH];
+            lone_board[from] = Some(piece);
 
-                if matches!(piece.get_piece_type(), PieceType::Pawn { en_passant: _ })
-                    && y == promotionable_row
-                {
-                    let promoter = Promoter::new(
-                        self.promoter_position,
-                        (x, y),
-                        piece.get_color(),
-                    );
+            let mut unobstructed = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+            move_calculator::get_pseudo_legal_moves(&lone_board, from, None, &mut unobstructed);
 
-                    self.turn_info.state = TurnState::Promotion(promoter);
+            return if unobstructed[to] {
+                format!("The {name} on {from} is blocked")
+            } else {
+                format!("The {name} doesn't move like that")
+            };
+        }
 
-                    delay_turn = true;
-                }
+        // the move was taken out by the legality checks
+        if piece.get_piece_type() == PieceType::King {
+            if from.x().abs_diff(to.x()) == 2 {
+                "The king may not castle out of, through, or into check".to_string()
+            } else {
+                format!("The king would be in check on {to}")
             }
+        } else if !self.position_cache.check_rays.is_empty() {
+            "That doesn't get the king out of check".to_string()
+        } else {
+            format!("The {name} on {from} is pinned to the king")
         }
+    }
 
-        if !delay_turn {
-            self.change_turn = true;
-        }
+    fn get_moves_from(&self, square: Square) -> &[Move] {
+        let (start, end) = self.legal_move_ranges[square];
+
+        &self.legal_moves[start..end]
     }
 
-    fn change_turn(&mut self) {
-        self.change_turn = false;
+    // the move of the selected piece to the given cell, if it's legal
+    fn get_selected_move(&self, to: Square) -> Option<Move> {
+        self.selected_cell.and_then(|from| self.find_legal_move(from, to))
+    }
 
-        self.turn_info.color = self.turn_info.color.get_enemy_color();
-        self.selected_cell = None;
+    fn find_legal_move(&self, from: Square, to: Square) -> Option<Move> {
+        self.get_moves_from(from)
+            .iter()
+            .find(|legal_move| legal_move.to == to)
+            .copied()
+    }
 
-        self.turn_info.state = if self.is_in_check(self.turn_info.color) {
-            TurnState::Check
-        } else {
-            TurnState::Normal
-        };
+    // whether the board changed since the last call.
+    // a turn started by a drag ends on the next frame, when there's no input to redraw for
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.is_changed)
+    }
 
-        self.compute_moves();
+    pub fn get_played_moves(&self) -> &[(Move, Option<PieceType>)] {
+        &self.played_moves
     }
 
-    fn compute_is_movable(&mut self) {
-        let zipped = iter::zip(
-            self.is_movable.iter_mut().flatten(),
-            self.legal_moves.iter().flatten(),
-        );
+    // events queued since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
 
-        // set is_movable[x][y] to true iff legal_moves[x][y] contains true
-        for (is_movable, legal_moves) in zipped {
-            *is_movable = legal_moves.iter().any(|col| col.contains(&true));
-        }
+    pub fn get_board(&self) -> &Board<Option<Piece>> {
+        &self.board
     }
 
-    pub fn draw(
-        &self,
-        ctx: &mut Context,
-        canvas: &mut graphics::Canvas,
-        assets: &mut Assets,
-    ) -> GameResult {
-        self.draw_turn_state(canvas);
-        self.draw_board(canvas, self.position, CELL_SIZE);
-        self.draw_pieces(ctx, canvas, assets, self.position, CELL_SIZE);
+    pub fn get_turn_color(&self) -> PieceColor {
+        self.turn_info.color
+    }
 
-        if let TurnState::Promotion(promoter) = &self.turn_info.state {
-            (*promoter).draw(ctx, canvas, assets)?;
+    pub fn get_fen(&self) -> String {
+        fen::to_fen(&self.board, self.turn_info.color, self.en_passant_target)
+    }
+
+    pub fn get_snapshot(&self) -> PositionSnapshot {
+        PositionSnapshot::new(&self.board, self.turn_info.color, self.en_passant_target)
+    }
+
+    // short description of the game state, e.g. "White to move"
+    pub fn get_status(&self) -> String {
+        let color = self.turn_info.color.get_name();
+
+        match self.turn_info.state {
+            TurnState::Normal | TurnState::Promotion(..) => format!("{color} to move"),
+            TurnState::Check => format!("{color} to move, in check"),
+            TurnState::Checkmate => format!(
+                "Checkmate, {} wins",
+                self.turn_info.color.get_enemy_color().get_name()
+            ),
+            TurnState::Stalemate => "Stalemate, draw".to_string(),
+        }
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.turn_info.state == TurnState::Checkmate
+    }
+
+    // whether a promoted pawn is waiting for the player to choose its piece
+    pub fn is_promotion_pending(&self) -> bool {
+        matches!(self.turn_info.state, TurnState::Promotion(..))
+    }
+
+    // legal moves of the side to move, empty once the game has ended
+    pub fn get_legal_moves(&self) -> &[Move] {
+        &self.legal_moves
+    }
+
+    pub fn is_empty_on(board_state: &Board<Option<Piece>>, square: Square) -> bool {
+        board_state[square].is_none()
+    }
+
+    pub fn is_color_on(
+        board_state: &Board<Option<Piece>>,
+        square: Square,
+        color: PieceColor,
+    ) -> bool {
+        let Some(piece) = board_state[square] else { return false; };
+        piece.get_color() == color
+    }
+
+    fn try_select_cell(&self, mouse: &Mouse) -> Option<Square> {
+        self.get_cell_at(mouse.get_mouse())
+    }
+
+    // top-left corner of the cell on the screen
+    fn get_cell_position(&self, cell: Square) -> Vec2 {
+        self.position + vec2(CELL_SIZE * cell.x() as f32, CELL_SIZE * cell.y() as f32)
+    }
+
+    fn get_cell_at(&self, pos: Vec2) -> Option<Square> {
+        let cell = ((pos - self.position) / CELL_SIZE).floor();
+
+        Square::try_new(cell.x as i32, cell.y as i32)
+    }
+
+    // play a move of the current turn for real,
+    // as opposed to the simulated moves of the legality checks
+    fn play_move(&mut self, from: Square, to: Square, auto_queen: bool) -> Result<(), MoveError> {
+        let piece = self.board[from].ok_or(MoveError::EmptyOrigin(from))?;
+        let played_move = Move::new(&self.board, from, to);
+
+        let captured = self.move_piece(from, to)?;
+
+        self.events.push(GameEvent::MovePlayed { piece, from, to });
+        self.played_moves.push((played_move, None));
+
+        if let Some(captured) = captured {
+            self.events.push(GameEvent::Capture { captured, cell: to });
         }
 
+        self.post_move_update(auto_queen);
+
         Ok(())
     }
 
-    fn draw_turn_state(&self, canvas: &mut graphics::Canvas) {
-        let turn_text = graphics::Text::new(format!(
-            "{}'s turn",
-            if self.turn_info.color == PieceColor::White {
-                "White"
+    // returns the captured piece, if any.
+    // nothing is moved if the move can't be made.
+    fn move_piece(&mut self, from: Square, to: Square) -> Result<Option<Piece>, MoveError> {
+        let undo = self.make_move(from, to)?;
+
+        Ok(undo.captured.map(|(_, captured)| captured))
+    }
+
+    // move the piece along with the side effects of the move,
+    // returning what it takes to unmake it
+    fn make_move(&mut self, from: Square, to: Square) -> Result<Undo, MoveError> {
+        let piece = self.board[from].ok_or(MoveError::EmptyOrigin(from))?;
+
+        let mut moved = piece;
+        moved.set_has_moved(true);
+
+        // handle special moves
+        let captured_cell = self.get_captured_cell(from, to, &piece);
+        let castling_rook = self.move_castling(from, to, &moved)?;
+
+        let captured = self.board[captured_cell].map(|captured| (captured_cell, captured));
+
+        self.board[from] = None;
+        self.board[captured_cell] = None;
+        self.board[to] = Some(moved);
+
+        let en_passant_target = self.en_passant_target;
+        self.en_passant_target = Chess::get_skipped_cell(from, to, &piece);
+
+        Ok(Undo {
+            from,
+            to,
+            piece,
+            captured,
+            castling_rook,
+            en_passant_target,
+        })
+    }
+
+    // put the board back exactly as it was before the move
+    fn unmake_move(&mut self, undo: Undo) {
+        self.board[undo.to] = None;
+
+        if let Some((rook_from, rook_to, rook)) = undo.castling_rook {
+            self.board[rook_to] = None;
+            self.board[rook_from] = Some(rook);
+        }
+
+        if let Some((cell, captured)) = undo.captured {
+            self.board[cell] = Some(captured);
+        }
+
+        self.board[undo.from] = Some(undo.piece);
+        self.en_passant_target = undo.en_passant_target;
+    }
+
+    // move the rook of a castling king, returning where it was and went
+    fn move_castling(
+        &mut self,
+        from: Square,
+        to: Square,
+        king: &Piece,
+    ) -> Result<Option<(Square, Square, Piece)>, MoveError> {
+        let is_castling =
+            (king.get_piece_type() == PieceType::King) && (from.x().abs_diff(to.x()) == 2);
+
+        if !is_castling {
+            return Ok(None);
+        };
+
+        let rook_x = if to.x() > from.x() { BOARD_WIDTH - 1 } else { 0 };
+        let rook_new_x = if to.x() > from.x() { to.x() - 1 } else { to.x() + 1 };
+
+        let rook_from = Square::new(rook_x, from.y());
+        let rook_to = Square::new(rook_new_x, from.y());
+
+        let rook = self.board[rook_from]
+            .filter(|rook| {
+                rook.get_color() == king.get_color() && rook.get_piece_type() == PieceType::Rook
+            })
+            .ok_or(MoveError::MissingRook(rook_from))?;
+
+        let mut moved_rook = rook;
+        moved_rook.set_has_moved(true);
+
+        self.board[rook_from] = None;
+        self.board[rook_to] = Some(moved_rook);
+
+        Ok(Some((rook_from, rook_to, rook)))
+    }
+
+    // the cell of the piece a move captures, which is not the destination
+    // for an en passant capture
+    fn get_captured_cell(&self, from: Square, to: Square, piece: &Piece) -> Square {
+        let is_en_passant = piece.get_piece_type() == PieceType::Pawn
+            && from.x() != to.x()
+            && self.en_passant_target == Some(to);
+
+        if is_en_passant {
+            Square::new(to.x(), from.y())
+        } else {
+            to
+        }
+    }
+
+    // the cell a pawn skips when it moves two cells, None for any other move
+    fn get_skipped_cell(from: Square, to: Square, piece: &Piece) -> Option<Square> {
+        let is_double_step = piece.get_piece_type() == PieceType::Pawn
+            && from.x() == to.x()
+            && from.y().abs_diff(to.y()) == 2;
+
+        is_double_step.then(|| Square::new(from.x(), (from.y() + to.y()) / 2))
+    }
+
+    // compute and populate each piece's legal moves
+    fn compute_each_legal_moves(&mut self) {
+        // reset previous legal moves
+        self.clear_legal_moves();
+
+        // iterate each piece of current turn and compute its legal moves
+        for from in Square::all() {
+            if !Chess::is_color_on(&self.board, from, self.turn_info.color) {
+                continue;
+            }
+
+            let mut moves = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+
+            move_calculator::get_pseudo_legal_moves(
+                &self.board,
+                from,
+                self.en_passant_target,
+                &mut moves,
+            );
+
+            self.eliminate_illegal_moves(from, &mut moves);
+
+            let start = self.legal_moves.len();
+
+            for to in Square::all() {
+                if moves[to] {
+                    self.legal_moves.push(Move::new(&self.board, from, to));
+                }
+            }
+
+            self.legal_move_ranges[from] = (start, self.legal_moves.len());
+        }
+    }
+
+    fn clear_legal_moves(&mut self) {
+        self.legal_moves.clear();
+        self.legal_move_ranges = [[(0, 0); BOARD_HEIGHT]; BOARD_WIDTH];
+    }
+
+    fn eliminate_illegal_moves(&mut self, from: Square, moves: &mut Board<bool>) {
+        for to in Square::all() {
+            if !moves[to] {
+                continue;
+            }
+
+            // temporarily make the move to see if it leaves the king in check
+            match self.make_move(from, to) {
+                Ok(undo) => {
+                    moves[to] = !self.is_in_check(self.turn_info.color);
+                    self.unmake_move(undo);
+                }
+                Err(..) => moves[to] = false,
+            }
+        }
+
+        // special case: check illegal moves for castling
+        self.eliminate_castling_illegal_moves(from, moves);
+    }
+
+    fn eliminate_castling_illegal_moves(&self, from: Square, moves: &mut Board<bool>) {
+        let Some(piece) = self.board[from] else { return };
+
+        if piece.get_piece_type() != PieceType::King {
+            return;
+        }
+
+        let enemy_attack_counts = match piece.get_color() {
+            PieceColor::White => &self.position_cache.black_attack_counts,
+            PieceColor::Black => &self.position_cache.white_attack_counts,
+        };
+
+        for x_dir in [-1, 1] {
+            let Some(castling_dst) = from.offset((x_dir * 2, 0)) else { continue };
+
+            if moves[castling_dst]
+                && !move_calculator::is_castling_safe(from, x_dir, enemy_attack_counts)
+            {
+                moves[castling_dst] = false;
+            }
+        }
+    }
+
+    fn find_king(&self, color: PieceColor) -> Square {
+        Square::all()
+            .find(|&square| {
+                self.board[square].is_some_and(|piece| {
+                    piece.get_color() == color && piece.get_piece_type() == PieceType::King
+                })
+            })
+            .expect("king not found in the board")
+    }
+
+    pub fn is_in_check(&self, color: PieceColor) -> bool {
+        let kings_position = self.find_king(color);
+
+        move_calculator::is_square_attacked(&self.board, kings_position, color.get_enemy_color())
+    }
+
+    fn post_move_update(&mut self, auto_queen: bool) {
+        let mut delay_turn = false;
+
+        for x in 0..BOARD_WIDTH {
+            for y in 0..BOARD_HEIGHT {
+                let Some(piece) = &mut self.board[x][y] else { continue };
+
+                // update promotion
+                let promotionable_row = match piece.get_color() {
+                    PieceColor::White => 0,
+                    PieceColor::Black => BOARD_HEIGHT - 1,
+                };
+
+                if piece.get_piece_type() == PieceType::Pawn
+                    && y == promotionable_row
+                {
+                    if auto_queen {
+                        piece.promote(PieceType::Queen);
+
+                        self.events.push(GameEvent::Promotion {
+                            color: piece.get_color(),
+                            cell: Square::new(x, y),
+                            promote_to: PieceType::Queen,
+                        });
+
+                        continue;
+                    }
+
+                    let promoter = Promoter::new(
+                        self.promoter_position,
+                        Square::new(x, y),
+                        piece.get_color(),
+                    );
+
+                    self.turn_info.state = TurnState::Promotion(promoter);
+
+                    // the mover keeps the turn, but only to choose the piece
+                    self.clear_legal_moves();
+
+                    delay_turn = true;
+                }
+            }
+        }
+
+        // the promotion popup is drawn over the position after the move
+        self.refresh_position_cache();
+
+        if !delay_turn {
+            self.change_turn = true;
+        } else {
+            self.try_show_rule_tip();
+        }
+    }
+
+    fn finish_turn(&mut self) {
+        self.change_turn();
+        self.record_activity();
+        self.push_turn_events();
+    }
+
+    fn push_turn_events(&mut self) {
+        let color = self.turn_info.color;
+
+        match self.turn_info.state {
+            TurnState::Check => self.events.push(GameEvent::Check { color }),
+            TurnState::Checkmate => {
+                self.events.push(GameEvent::Check { color });
+                self.events.push(GameEvent::GameEnded(GameEnd::Checkmate {
+                    winner: color.get_enemy_color(),
+                }));
+            }
+            TurnState::Stalemate => self.events.push(GameEvent::GameEnded(GameEnd::Stalemate)),
+            _ => {}
+        }
+    }
+
+    fn try_show_rule_tip(&mut self) {
+        if !self.show_rule_tips || self.low_latency {
+            return;
+        }
+
+        let tip = self
+            .find_rule_tips()
+            .into_iter()
+            .find(|tip| !self.seen_rules.contains(&tip.rule));
+
+        if let Some(tip) = tip {
+            self.seen_rules.push(tip.rule);
+            self.rule_tip = Some(tip);
+        }
+    }
+
+    // special rules that apply to the current turn
+    fn find_rule_tips(&self) -> Vec<RuleTip> {
+        match &self.turn_info.state {
+            TurnState::Promotion(promoter) => {
+                return vec![RuleTip {
+                    rule: Rule::Promotion,
+                    cells: vec![promoter.cell],
+                }];
+            }
+            TurnState::Stalemate => {
+                return vec![RuleTip {
+                    rule: Rule::Stalemate,
+                    cells: Vec::new(),
+                }];
+            }
+            _ => {}
+        }
+
+        let mut tips = Vec::new();
+
+        for &Move { from, to, kind, .. } in &self.legal_moves {
+            match kind {
+                MoveKind::EnPassant => {
+                    tips.push(RuleTip {
+                        rule: Rule::EnPassant,
+                        cells: vec![from, to, Square::new(to.x(), from.y())],
+                    });
+                }
+                MoveKind::Castling => {
+                    tips.push(RuleTip {
+                        rule: Rule::Castling,
+                        cells: vec![from, to],
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        tips
+    }
+
+    fn record_activity(&mut self) {
+        let white_counts = &self.position_cache.white_attack_counts;
+        let black_counts = &self.position_cache.black_attack_counts;
+
+        for x in 0..BOARD_WIDTH {
+            for y in 0..BOARD_HEIGHT {
+                let occupied = u32::from(self.board[x][y].is_some());
+
+                self.activity[x][y] += occupied + white_counts[x][y] + black_counts[x][y];
+            }
+        }
+    }
+
+    fn change_turn(&mut self) {
+        self.change_turn = false;
+        self.is_changed = true;
+
+        self.turn_info.color = self.turn_info.color.get_enemy_color();
+        self.selected_cell = None;
+
+        self.refresh_position_cache();
+
+        self.turn_info.state = if !self.position_cache.check_rays.is_empty() {
+            TurnState::Check
+        } else {
+            TurnState::Normal
+        };
+
+        self.compute_moves();
+    }
+
+    fn refresh_position_cache(&mut self) {
+        self.position_cache = PositionCache::new(&self.board, self.turn_info.color);
+    }
+
+    // play one of the legal moves on a copy of the game and hand the turn to the opponent.
+    // promotions are assumed to be to a queen.
+    pub fn simulate_move(&self, simulated_move: &Move) -> Result<Chess, MoveError> {
+        let &Move { from, to, is_promotion, .. } = simulated_move;
+
+        if let TurnState::Promotion(..) = self.turn_info.state {
+            return Err(MoveError::PendingPromotion);
+        }
+
+        if !self.legal_moves.contains(simulated_move) {
+            return Err(MoveError::Illegal { from, to });
+        }
+
+        let mut simulated = self.clone();
+
+        simulated.move_piece(from, to)?;
+
+        if is_promotion {
+            simulated.board[to]
+                .as_mut()
+                .expect("the promoted pawn should be on the destination")
+                .promote(PieceType::Queen);
+        }
+
+        simulated.change_turn();
+
+        Ok(simulated)
+    }
+
+    // quick two-ply look at the opponent's replies.
+    // returns a description of the threat if the move allows mate or loses material.
+    fn find_blunder(&self, legal_move: &Move) -> Option<String> {
+        let mover = self.turn_info.color;
+        let enemy_color = mover.get_enemy_color();
+
+        let to = legal_move.to;
+        let gained = self.board[to].map_or(0, |piece| piece.get_piece_type().get_value());
+
+        let after = self.simulate_move(legal_move).ok()?;
+
+        let mut worst_loss = 0;
+        let mut threat = None;
+
+        for reply_move in &after.legal_moves {
+            let (reply_from, reply_to) = (reply_move.from, reply_move.to);
+
+            let attacker = after.board[reply_from]
+                .expect("the origin of a legal move should contain a piece");
+
+            let Ok(reply) = after.simulate_move(reply_move) else { continue };
+
+            if reply.turn_info.state == TurnState::Checkmate {
+                return Some(format!(
+                    "{} can checkmate with {} {} to {}",
+                    enemy_color.get_name(),
+                    attacker.get_piece_type().get_name(),
+                    reply_from,
+                    reply_to,
+                ));
+            }
+
+            if !reply_move.is_capture() {
+                continue;
+            }
+
+            let victim_cell = if reply_move.kind == MoveKind::EnPassant {
+                Square::new(reply_to.x(), reply_from.y())
             } else {
-                "Black"
+                reply_to
+            };
+
+            let victim = after.board[victim_cell]
+                .expect("a capture should have a victim");
+
+            let victim_value = victim.get_piece_type().get_value();
+            let attacker_value = attacker.get_piece_type().get_value();
+
+            // can the captured piece be taken back?
+            let is_defended = reply
+                .legal_moves
+                .iter()
+                .any(|recapture| recapture.to == reply_to);
+
+            let loss = if is_defended {
+                victim_value.saturating_sub(attacker_value)
+            } else {
+                victim_value
+            };
+
+            if loss > worst_loss {
+                worst_loss = loss;
+                threat = Some(format!(
+                    "Your {} on {} can be taken by the {} on {}",
+                    victim.get_piece_type().get_name(),
+                    victim_cell,
+                    attacker.get_piece_type().get_name(),
+                    reply_from,
+                ));
             }
-        ))
-        //.set_font("LiberationMono")
-        .set_scale(32.)
-        .clone();
+        }
 
-        let mut state_text = match self.turn_info.state {
-            TurnState::Normal => "Normal",
-            TurnState::Promotion(..) => "Promote",
-            TurnState::Check => "Check",
-            TurnState::Checkmate => "Checkmate",
-            TurnState::Stalemate => "Stalemate",
+        if worst_loss > gained {
+            threat
+        } else {
+            None
+        }
+    }
+
+    // notice is a message of the game around the board, shown with the board's own hints
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        canvas: &mut graphics::Canvas,
+        assets: &Assets,
+        hud: &mut Hud,
+        notice: Option<&str>,
+    ) -> GameResult {
+        if !self.zen_mode {
+            self.draw_turn_state(canvas, hud);
+        }
+        self.draw_board(canvas, self.position, CELL_SIZE);
+
+        if self.show_heat_map && !self.zen_mode {
+            self.draw_heat_map(canvas);
+        }
+
+        if self.is_explaining_check() {
+            self.draw_check_rays(ctx, canvas)?;
+        }
+        self.draw_pieces(ctx, canvas, assets, self.position, CELL_SIZE);
+
+        if self.show_attack_counts && !self.zen_mode {
+            if let Some(cell) = self.hovered_cell {
+                self.draw_attack_counts(canvas, cell);
+            }
+        }
+
+        if let TurnState::Promotion(promoter) = &self.turn_info.state {
+            (*promoter).draw(canvas, assets);
+        }
+
+        if let Some(pending) = &self.pending_move {
+            self.draw_pending_move(canvas, pending);
+        }
+
+        let mut hints = self.get_hints();
+        hints.extend(notice.map(|notice| (notice.to_string(), graphics::Color::BLACK)));
+
+        Chess::draw_hints(ctx, canvas, &hints)?;
+
+        if let Some(tip) = &self.rule_tip {
+            let text = format!("{}\n\nClick to continue", tip.rule.get_explanation());
+
+            Chess::draw_popup(canvas, &text, vec2(6.0 * CELL_SIZE, 3.0 * CELL_SIZE));
+        }
+
+        Ok(())
+    }
+
+    // whether the board looks different as time passes, even without any input:
+    // the pulse runs every frame, and the illegal move reason disappears after a while
+    pub fn is_animating(&self) -> bool {
+        self.is_pulsing_movable() || self.illegal_move_reason.is_some()
+    }
+
+    pub fn get_graphics_quality(&self) -> GraphicsQuality {
+        self.graphics_quality
+    }
+
+    fn is_pulsing_movable(&self) -> bool {
+        self.pulse_movable && !self.low_latency && self.graphics_quality != GraphicsQuality::Off
+    }
+
+    fn is_explaining_check(&self) -> bool {
+        self.explain_check
+            && !self.zen_mode
+            && matches!(self.turn_info.state, TurnState::Check | TurnState::Checkmate)
+    }
+
+    // tint the cells of each checking line and connect the checking piece to the king
+    fn draw_check_rays(&self, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+        let ray_color = graphics::Color::from_rgba_u32(0xFF000044);
+        let line_color = graphics::Color::from_rgba_u32(0xFF0000AA);
+
+        let get_cell_center = |cell: Square| {
+            self.get_cell_position(cell) + vec2(CELL_SIZE / 2.0, CELL_SIZE / 2.0)
         };
 
-        let check_text = graphics::Text::new(state_text)
-            .set_scale(32.)
+        let king = self.find_king(self.turn_info.color);
+
+        for ray in &self.position_cache.check_rays {
+            for &cell in ray {
+                let param = graphics::DrawParam::default()
+                    .scale([CELL_SIZE, CELL_SIZE])
+                    .dest(self.get_cell_position(cell))
+                    .color(ray_color);
+
+                canvas.draw(&graphics::Quad, param);
+            }
+
+            let line = graphics::Mesh::new_line(
+                ctx,
+                &[get_cell_center(ray[0]), get_cell_center(king)],
+                6.,
+                line_color,
+            )?;
+
+            canvas.draw(&line, graphics::DrawParam::default());
+        }
+
+        Ok(())
+    }
+
+    // the most active cell is drawn fully saturated, the others proportionally
+    fn draw_heat_map(&self, canvas: &mut graphics::Canvas) {
+        let max_activity = *self.activity.iter().flatten().max().unwrap_or(&0);
+
+        if max_activity == 0 {
+            return;
+        }
+
+        for x in 0..BOARD_WIDTH {
+            for y in 0..BOARD_HEIGHT {
+                let heat = self.activity[x][y] as f32 / max_activity as f32;
+
+                let cell_pos = self.position + vec2(CELL_SIZE * x as f32, CELL_SIZE * y as f32);
+                let param = graphics::DrawParam::default()
+                    .scale([CELL_SIZE, CELL_SIZE])
+                    .dest(cell_pos)
+                    .color(graphics::Color::new(1.0, 0.4, 0.0, 0.7 * heat));
+
+                canvas.draw(&graphics::Quad, param);
+            }
+        }
+    }
+
+    // white attackers in the top-left corner, black attackers in the bottom-right corner
+    fn draw_attack_counts(&self, canvas: &mut graphics::Canvas, cell: Square) {
+        let white_counts = &self.position_cache.white_attack_counts;
+        let black_counts = &self.position_cache.black_attack_counts;
+
+        let (x, y) = (cell.x(), cell.y());
+
+        let cell_pos = self.position + vec2(CELL_SIZE * x as f32, CELL_SIZE * y as f32);
+        let margin = 4.;
+
+        let corners = [
+            (
+                white_counts[cell],
+                cell_pos + vec2(margin, margin),
+                TextAlign::Begin,
+                graphics::Color::WHITE,
+            ),
+            (
+                black_counts[cell],
+                cell_pos + vec2(CELL_SIZE - margin, CELL_SIZE - margin),
+                TextAlign::End,
+                graphics::Color::BLACK,
+            ),
+        ];
+
+        for (count, pos, align, color) in corners {
+            let text = graphics::Text::new(count.to_string())
+                .set_scale(20.)
+                .set_layout(TextLayout {
+                    h_align: align,
+                    v_align: align,
+                })
+                .clone();
+
+            canvas.draw(&text, graphics::DrawParam::from(pos).color(color));
+        }
+    }
+
+    // the hints above the board, the most urgent first.
+    // zen mode leaves them out, the highlighted destination is enough to confirm with
+    fn get_hints(&self) -> Vec<(String, graphics::Color)> {
+        let mut hints = Vec::new();
+
+        if self.zen_mode {
+            return hints;
+        }
+
+        if let Some((reason, _)) = &self.illegal_move_reason {
+            hints.push((reason.clone(), graphics::Color::from_rgb_u32(0xB00000)));
+        }
+
+        // a plain confirmation only needs a hint, a threat gets a popup
+        let confirmation = self.pending_move.as_ref().filter(|pending| pending.threat.is_none());
+
+        if let Some(pending) = confirmation {
+            let text = format!("Click {} again to confirm", pending.to);
+
+            hints.push((text, graphics::Color::BLACK));
+        }
+
+        hints
+    }
+
+    fn draw_pending_move(&self, canvas: &mut graphics::Canvas, pending: &PendingMove) {
+        let Some(threat) = &pending.threat else { return };
+
+        let text = format!(
+            "Are you sure?\n{}\n\nClick {} again to play it",
+            threat, pending.to,
+        );
+
+        Chess::draw_popup(canvas, &text, vec2(6.0 * CELL_SIZE, 2.0 * CELL_SIZE));
+    }
+
+    // lines of text centered above the board, each hint below the one before
+    fn draw_hints(
+        ctx: &Context,
+        canvas: &mut graphics::Canvas,
+        hints: &[(String, graphics::Color)],
+    ) -> GameResult {
+        let mut y = 50.;
+
+        for (text, color) in hints {
+            let text = graphics::Text::new(text.as_str())
+                .set_scale(24.)
+                .set_layout(TextLayout {
+                    h_align: TextAlign::Middle,
+                    v_align: TextAlign::Begin,
+                })
+                .clone();
+
+            canvas.draw(
+                &text,
+                graphics::DrawParam::from(vec2(WINDOW_WIDTH / 2.0, y)).color(*color),
+            );
+
+            y += text.measure(ctx)?.y;
+        }
+
+        Ok(())
+    }
+
+    // dark box with centered white text in the middle of the window
+    pub fn draw_popup(canvas: &mut graphics::Canvas, text: &str, size: Vec2) {
+        let center = vec2(WINDOW_WIDTH / 2.0, WINDOW_HEIGHT / 2.0);
+
+        canvas.draw(
+            &graphics::Quad,
+            graphics::DrawParam::default()
+                .dest(center - size / 2.0)
+                .scale(size)
+                .color(graphics::Color::from_rgba_u32(0x000000CC)),
+        );
+
+        let text = graphics::Text::new(text)
+            .set_scale(20.)
+            .set_bounds(size - vec2(20., 20.))
             .set_layout(TextLayout {
-                // right align
-                h_align: TextAlign::End,
-                v_align: TextAlign::Begin,
+                h_align: TextAlign::Middle,
+                v_align: TextAlign::Middle,
             })
             .clone();
 
         canvas.draw(
-            &turn_text,
-            graphics::DrawParam::from(vec2(15., 15.)).color(graphics::Color::from((0, 0, 0, 255))),
+            &text,
+            graphics::DrawParam::from(center).color(graphics::Color::WHITE),
         );
+    }
+
+    fn draw_turn_state(&self, canvas: &mut graphics::Canvas, hud: &mut Hud) {
+        let turn_text = format!("{}'s turn", self.turn_info.color.get_name());
+
+        hud.turn.draw(canvas, &turn_text, vec2(15., 15.));
+
+        let state_text = match self.turn_info.state {
+            TurnState::Normal => "Normal",
+            TurnState::Promotion(..) => "Promote",
+            TurnState::Check => "Check",
+            TurnState::Checkmate => "Checkmate",
+            TurnState::Stalemate => "Stalemate",
+        };
 
         if self.turn_info.state != TurnState::Normal {
-            canvas.draw(
-                &check_text,
-                graphics::DrawParam::from(vec2(WINDOW_WIDTH - 15., 15.))
-                    .color(graphics::Color::from((0, 0, 0, 255))),
-            );
+            hud.state.draw(canvas, state_text, vec2(WINDOW_WIDTH - 15., 15.));
         }
+
+        let modes: Vec<&str> = [
+            (self.assist_mode, "Assist mode (A)"),
+            (self.confirm_moves, "Confirm moves (M)"),
+            (self.show_attack_counts, "Attack counts (C)"),
+            (self.show_heat_map, "Heat map (H)"),
+            (self.pulse_movable, "Pulse movable pieces (P)"),
+            (self.explain_check, "Explain check (E)"),
+            (self.auto_queen, "Auto-queen (Q)"),
+            (self.show_rule_tips, "Rule tips (T)"),
+            (self.low_latency, "Low latency (L)"),
+            (self.iccf_notation, "ICCF notation (V)"),
+            // the default isn't worth mentioning
+            (
+                self.graphics_quality != GraphicsQuality::High,
+                self.graphics_quality.get_label(),
+            ),
+        ]
+        .iter()
+        .filter_map(|&(is_on, label)| is_on.then_some(label))
+        .collect();
+
+        if !modes.is_empty() {
+            hud.modes.draw(canvas, &modes.join("   "), vec2(15., WINDOW_HEIGHT - 15.));
+        }
+
+        hud.moves.draw(canvas, &self.get_move_list(), vec2(WINDOW_WIDTH - 8., CELL_SIZE + 10.));
+    }
+
+    // the last moves one per line, white's numbered, in the notation picked with V
+    fn get_move_list(&self) -> String {
+        let lines: Vec<String> = self
+            .played_moves
+            .iter()
+            .enumerate()
+            .map(|(ind, (played_move, promote_to))| {
+                let text = if self.iccf_notation {
+                    pgn::to_iccf(played_move, *promote_to)
+                } else {
+                    let promote_to = promote_to.map_or(String::new(), |piece| piece.to_string());
+
+                    format!("{}{}{promote_to}", played_move.from, played_move.to)
+                };
+
+                // counting plies as if white moved first, the move counters of a FEN aren't kept
+                let ply = ind + usize::from(self.starting_color == PieceColor::Black);
+
+                match (ply % 2, ind) {
+                    (0, _) => format!("{}. {text}", ply / 2 + 1),
+                    (_, 0) => format!("{}... {text}", ply / 2 + 1),
+                    _ => text,
+                }
+            })
+            .collect();
+
+        lines[lines.len().saturating_sub(MOVE_LIST_PLIES)..].join("\n")
     }
 
     fn draw_board(&self, canvas: &mut graphics::Canvas, pos: Vec2, cell_size: f32) {
@@ -625,11 +1725,15 @@ impl Chess {
         let dark_color = graphics::Color::from_rgb_u32(0x434347);
         let select_color = graphics::Color::from_rgba_u32(0xFF000066);
         let movable_color = graphics::Color::from_rgba_u32(0x00FF0023);
+        let pending_color = graphics::Color::from_rgba_u32(0x0000FF66);
+        let tip_color = graphics::Color::from_rgba_u32(0xFFFF0088);
 
         let scale = [cell_size, cell_size];
 
         for cell_x in 0..BOARD_WIDTH {
             for cell_y in 0..BOARD_HEIGHT {
+                let cell = Square::new(cell_x, cell_y);
+
                 let color = if (cell_x + cell_y) % 2 == 0 {
                     light_color
                 } else {
@@ -643,8 +1747,17 @@ impl Chess {
                 // draw checker pattern
                 canvas.draw(&graphics::Quad, param.color(color));
 
+                if self
+                    .rule_tip
+                    .as_ref()
+                    .is_some_and(|tip| tip.cells.contains(&cell))
+                {
+                    canvas.draw(&graphics::Quad, param.color(tip_color));
+                    continue;
+                }
+
                 if let TurnState::Promotion(promoter) = &self.turn_info.state {
-                    if promoter.cell == (cell_x, cell_y) {
+                    if promoter.cell == cell {
                         canvas.draw(&graphics::Quad, param.color(select_color));
                     }
 
@@ -653,19 +1766,25 @@ impl Chess {
                 }
 
                 // draw transparent highlight on the selected cell and its movable cells
-                let is_selected_cell = self
+                let is_selected_cell = self.selected_cell == Some(cell);
+
+                let is_movable_cell = self
                     .selected_cell
-                    .is_some_and(|(sx, sy)| (sx, sy) == (cell_x, cell_y));
+                    .and_then(|selected| self.find_legal_move(selected, cell))
+                    .is_some();
 
-                let is_movable_cell = self.selected_cell.is_some_and(|selected| {
-                    self.legal_moves[selected.0][selected.1][cell_x][cell_y]
-                });
+                let is_movable_piece = !self.get_moves_from(cell).is_empty();
 
-                let is_movable_piece = self.is_movable[cell_x][cell_y];
+                let is_pending_cell = self
+                    .pending_move
+                    .as_ref()
+                    .is_some_and(|pending| pending.to == cell);
 
-                if is_selected_cell || is_movable_cell {
+                if is_pending_cell {
+                    canvas.draw(&graphics::Quad, param.color(pending_color));
+                } else if is_selected_cell || is_movable_cell {
                     canvas.draw(&graphics::Quad, param.color(select_color));
-                } else if is_movable_piece {
+                } else if is_movable_piece && !self.is_pulsing_movable() {
                     canvas.draw(&graphics::Quad, param.color(movable_color));
                 }
             }
@@ -676,30 +1795,73 @@ impl Chess {
         &self,
         ctx: &mut Context,
         canvas: &mut graphics::Canvas,
-        assets: &mut Assets,
+        assets: &Assets,
         pos: Vec2,
         cell_size: f32,
     ) {
-        let sprite_original_size = 460.0;
+        // breathe between 92% and 100% of the cell, once a second
+        let phase = ctx.time.time_since_start().as_secs_f32() * std::f32::consts::TAU;
+        let pulse = 0.96 + 0.04 * phase.sin();
+
+        let dragged_cell = self.drag_position.and(self.selected_cell);
 
         for cell_x in 0..BOARD_WIDTH {
             for cell_y in 0..BOARD_HEIGHT {
                 let Some(piece) = &self.board[cell_x][cell_y] else { continue };
 
+                if dragged_cell == Some(Square::new(cell_x, cell_y)) {
+                    continue;
+                }
+
                 // set pos to the center of the cell
                 let cell_pos = pos + vec2(cell_size * cell_x as f32, cell_size * cell_y as f32);
                 let cell_pos_centered = cell_pos + vec2(cell_size / 2.0, cell_size / 2.0);
 
-                let image = piece.get_image(ctx, assets);
-                let drawparams = graphics::DrawParam::new()
-                    .dest(cell_pos_centered)
-                    .offset([0.5, 0.5]) // offset so that the sprite center and the drawing position align
-                    .scale([
-                        cell_size / sprite_original_size,
-                        cell_size / sprite_original_size,
-                    ]);
-                canvas.draw(image, drawparams);
+                let is_pulsing = self.is_pulsing_movable()
+                    && !self.get_moves_from(Square::new(cell_x, cell_y)).is_empty();
+
+                let sprite_size = if is_pulsing { cell_size * pulse } else { cell_size };
+
+                // pieces that can't get the king out of check fade out
+                let is_dimmed = self.is_explaining_check()
+                    && piece.get_color() == self.turn_info.color
+                    && self.get_moves_from(Square::new(cell_x, cell_y)).is_empty();
+
+                let alpha = if is_dimmed { 0.35 } else { 1.0 };
+
+                let tint = graphics::Color::new(1.0, 1.0, 1.0, alpha);
+
+                piece.draw(canvas, assets, cell_pos_centered, sprite_size, tint);
+            }
+        }
+
+        // the dragged piece is drawn last to stay on top of the others
+        let dragged = self.drag_position.zip(dragged_cell.and_then(|cell| self.board[cell]));
+
+        if let Some((drag_position, piece)) = dragged {
+            // a lifted piece casts a shadow on the board
+            if self.graphics_quality == GraphicsQuality::High {
+                let shadow_position = drag_position + vec2(cell_size * 0.06, cell_size * 0.1);
+                let shadow_tint = graphics::Color::new(0.0, 0.0, 0.0, 0.3);
+
+                piece.draw(canvas, assets, shadow_position, cell_size, shadow_tint);
             }
+
+            piece.draw(canvas, assets, drag_position, cell_size, graphics::Color::WHITE);
+        }
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MoveError::*;
+
+        match self {
+            EmptyOrigin(cell) => write!(f, "There's no piece on {cell}"),
+            WrongColor(cell) => write!(f, "The piece on {cell} can't move this turn"),
+            Illegal { from, to } => write!(f, "{from} to {to} is not a legal move"),
+            MissingRook(cell) => write!(f, "There's no rook on {cell} to castle with"),
+            PendingPromotion => write!(f, "The promoted pawn is waiting for its piece"),
         }
     }
 }
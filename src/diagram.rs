@@ -0,0 +1,145 @@
+use std::{fs, path::Path};
+
+use crate::{
+    chess::{BOARD_HEIGHT, BOARD_WIDTH},
+    pgn::Replay,
+    piece::*,
+    snapshot::PositionSnapshot,
+    square::Square,
+    vector_piece,
+};
+
+// sizes in svg user units, which print at 1/96 inch
+const CELL_SIZE: f32 = 45.0;
+// room around the board for the coordinates and the side to move marker
+const MARGIN: f32 = 30.0;
+const CAPTION_HEIGHT: f32 = 36.0;
+
+const LIGHT_COLOR: &str = "#FFFFFF";
+const DARK_COLOR: &str = "#BFBFBF";
+
+// the position as a standalone svg diagram, drawn as seen by white,
+// with the coordinates around the board, a marker next to the side to move and the caption below
+pub fn build_svg(position: &PositionSnapshot, caption: &str) -> String {
+    let board = position.get_board();
+
+    let board_width = CELL_SIZE * BOARD_WIDTH as f32;
+    let board_height = CELL_SIZE * BOARD_HEIGHT as f32;
+
+    let width = board_width + 2.0 * MARGIN;
+    let height = board_height + 2.0 * MARGIN + CAPTION_HEIGHT;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\">\n"
+    );
+
+    svg += &format!("<rect width=\"{width}\" height=\"{height}\" fill=\"#FFFFFF\"/>\n");
+
+    for square in Square::all() {
+        let (x, y) = get_cell_position(square);
+
+        let color = if (square.x() + square.y()) % 2 == 0 {
+            LIGHT_COLOR
+        } else {
+            DARK_COLOR
+        };
+
+        svg += &format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{CELL_SIZE}\" height=\"{CELL_SIZE}\" fill=\"{color}\"/>\n"
+        );
+    }
+
+    svg += &format!(
+        "<rect x=\"{MARGIN}\" y=\"{MARGIN}\" width=\"{board_width}\" height=\"{board_height}\" \
+         fill=\"none\" stroke=\"#000000\" stroke-width=\"1.5\"/>\n"
+    );
+
+    for square in Square::all() {
+        let Some(piece) = &board[square] else { continue };
+
+        let (x, y) = get_cell_position(square);
+        let center = (x + CELL_SIZE / 2.0, y + CELL_SIZE / 2.0);
+
+        svg += &vector_piece::build_piece_svg(piece, center, CELL_SIZE);
+        svg += "\n";
+    }
+
+    // files below the board and ranks to its left
+    for x in 0..BOARD_WIDTH {
+        let file = (b'a' + x as u8) as char;
+        let text_x = MARGIN + CELL_SIZE * (x as f32 + 0.5);
+        let text_y = MARGIN + board_height + MARGIN / 2.0;
+
+        svg += &get_text(text_x, text_y, 14.0, &file.to_string());
+    }
+
+    for y in 0..BOARD_HEIGHT {
+        let rank = BOARD_HEIGHT - y;
+        let text_y = MARGIN + CELL_SIZE * (y as f32 + 0.5);
+
+        svg += &get_text(MARGIN / 2.0, text_y, 14.0, &rank.to_string());
+    }
+
+    // a small disc of the moving side's color, next to its back rank
+    let (marker_y, marker_fill) = match position.get_turn_color() {
+        PieceColor::White => (MARGIN + board_height - CELL_SIZE / 2.0, "#FFFFFF"),
+        PieceColor::Black => (MARGIN + CELL_SIZE / 2.0, "#262626"),
+    };
+
+    svg += &format!(
+        "<circle cx=\"{}\" cy=\"{marker_y}\" r=\"8\" fill=\"{marker_fill}\" \
+         stroke=\"#000000\" stroke-width=\"1.5\"/>\n",
+        MARGIN + board_width + MARGIN / 2.0,
+    );
+
+    svg += &get_text(width / 2.0, height - CAPTION_HEIGHT / 2.0, 18.0, caption);
+
+    svg + "</svg>\n"
+}
+
+// save a diagram of the game's position after each of the given moves into the directory,
+// e.g. "12" for the position after white's twelfth move as 12.svg, and "12..." for black's
+pub fn render_pgn(pgn: &str, dir: &Path, move_numbers: &[String]) -> Result<(), String> {
+    let replay = Replay::from_pgn(pgn)?;
+
+    fs::create_dir_all(dir).map_err(|err| format!("cannot create {}: {err}", dir.display()))?;
+
+    for move_number in move_numbers {
+        let game = replay
+            .get_position_after(move_number)
+            .ok_or_else(|| format!("the game has no move {move_number}"))?;
+
+        let caption = format!("After {move_number}");
+        let svg = build_svg(&game.get_snapshot(), &caption);
+
+        let path = dir.join(format!("{}.svg", move_number.replace("...", "b")));
+
+        fs::write(&path, svg).map_err(|err| format!("cannot write {}: {err}", path.display()))?;
+
+        println!("saved {}", path.display());
+    }
+
+    Ok(())
+}
+
+// top-left corner of the cell in the diagram
+fn get_cell_position(square: Square) -> (f32, f32) {
+    (
+        MARGIN + CELL_SIZE * square.x() as f32,
+        MARGIN + CELL_SIZE * square.y() as f32,
+    )
+}
+
+// text centered on (x, y)
+fn get_text(x: f32, y: f32, size: f32, text: &str) -> String {
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    format!(
+        "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" text-anchor=\"middle\" \
+         dominant-baseline=\"central\">{text}</text>\n"
+    )
+}
@@ -0,0 +1,255 @@
+use crate::{
+    chess::{Board, BOARD_HEIGHT, BOARD_WIDTH},
+    move_calculator,
+    piece::*,
+    square::Square,
+};
+
+// in the order they're written, white's king side first
+pub const CASTLING_RIGHTS: [char; 4] = ['K', 'Q', 'k', 'q'];
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+// a position as FEN describes it: the board, the side to move and the en passant target
+pub type Position = (Board<Option<Piece>>, PieceColor, Option<Square>);
+
+// the position described by a FEN string, None if it's not a legal position.
+// the halfmove and fullmove counters are optional and ignored, since the game keeps neither.
+pub fn parse_fen(fen: &str) -> Option<Position> {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+
+    let (placement, color, castling, en_passant) = match fields[..] {
+        [placement, color, castling, en_passant, ..] if fields.len() <= 6 => {
+            (placement, color, castling, en_passant)
+        }
+        _ => return None,
+    };
+
+    let mut board = parse_placement(placement)?;
+
+    let color = match color {
+        "w" => PieceColor::White,
+        "b" => PieceColor::Black,
+        _ => return None,
+    };
+
+    for square in Square::all() {
+        let Some(piece) = &board[square] else { continue };
+
+        // pawns may only move two cells from their starting row
+        let start_row = match piece.get_color() {
+            PieceColor::White => BOARD_HEIGHT - 2,
+            PieceColor::Black => 1,
+        };
+
+        let has_moved = match piece.get_piece_type() {
+            PieceType::Pawn if square.y() == 0 || square.y() == BOARD_HEIGHT - 1 => {
+                return None;
+            }
+            PieceType::Pawn => square.y() != start_row,
+            // until the castling rights say otherwise
+            PieceType::King | PieceType::Rook => true,
+            _ => false,
+        };
+
+        board[square].as_mut()?.set_has_moved(has_moved);
+    }
+
+    if castling != "-" {
+        for right in castling.chars() {
+            set_castling_right(&mut board, right)?;
+        }
+    }
+
+    let en_passant_target = match en_passant {
+        "-" => None,
+        en_passant => {
+            let target = Square::from_algebraic(en_passant)?;
+
+            // the pawn that just moved two cells stands right past the target,
+            // and it came from right before it.
+            // the target is on the sixth rank after black's double step, the third after white's
+            let (pawn_color, dy, rank) = match color {
+                PieceColor::White => (PieceColor::Black, 1, 5),
+                PieceColor::Black => (PieceColor::White, -1, 2),
+            };
+
+            if target.rank() != rank {
+                return None;
+            }
+
+            let is_double_step = board[target.offset((0, dy))?].is_some_and(|pawn| {
+                pawn.get_color() == pawn_color && pawn.get_piece_type() == PieceType::Pawn
+            });
+
+            let is_path_empty =
+                board[target].is_none() && board[target.offset((0, -dy))?].is_none();
+
+            if !is_double_step || !is_path_empty {
+                return None;
+            }
+
+            Some(target)
+        }
+    };
+
+    for king_color in [PieceColor::White, PieceColor::Black] {
+        let mut kings = Square::all().filter(|&square| {
+            board[square].is_some_and(|piece| {
+                piece.get_color() == king_color && piece.get_piece_type() == PieceType::King
+            })
+        });
+
+        let (Some(king), None) = (kings.next(), kings.next()) else { return None };
+
+        // the side that just moved can't have left its king in check
+        if king_color != color && move_calculator::is_square_attacked(&board, king, color) {
+            return None;
+        }
+    }
+
+    Some((board, color, en_passant_target))
+}
+
+// the FEN string of a position.
+// the game doesn't count moves, so the halfmove and fullmove counters are always "0 1".
+pub fn to_fen(
+    board: &Board<Option<Piece>>,
+    color: PieceColor,
+    en_passant_target: Option<Square>,
+) -> String {
+    let rows: Vec<String> = (0..BOARD_HEIGHT)
+        .map(|y| {
+            let mut row = String::new();
+            let mut empty = 0;
+
+            for column in board {
+                let Some(piece) = &column[y] else {
+                    empty += 1;
+                    continue;
+                };
+
+                if empty > 0 {
+                    row += &empty.to_string();
+                    empty = 0;
+                }
+
+                row += &piece.to_string();
+            }
+
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+
+            row
+        })
+        .collect();
+
+    let color = match color {
+        PieceColor::White => "w",
+        PieceColor::Black => "b",
+    };
+
+    let castling: String = CASTLING_RIGHTS
+        .into_iter()
+        .filter(|&right| has_castling_right(board, right))
+        .collect();
+
+    let castling = if castling.is_empty() { "-".to_string() } else { castling };
+
+    let en_passant = en_passant_target.map_or("-".to_string(), |target| target.to_string());
+
+    format!("{} {color} {castling} {en_passant} 0 1", rows.join("/"))
+}
+
+fn parse_placement(placement: &str) -> Option<Board<Option<Piece>>> {
+    let mut board = [[None; BOARD_HEIGHT]; BOARD_WIDTH];
+
+    let rows: Vec<&str> = placement.split('/').collect();
+
+    if rows.len() != BOARD_HEIGHT {
+        return None;
+    }
+
+    // rows are listed from the eighth rank down, the same order as y
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0;
+
+        for curr in row.chars() {
+            if let Some(empty) = curr.to_digit(10) {
+                x += empty as usize;
+                continue;
+            }
+
+            let piece_type = match curr.to_ascii_lowercase() {
+                'r' => PieceType::Rook,
+                'n' => PieceType::Knight,
+                'b' => PieceType::Bishop,
+                'q' => PieceType::Queen,
+                'k' => PieceType::King,
+                'p' => PieceType::Pawn,
+                _ => return None,
+            };
+
+            let color = if curr.is_lowercase() {
+                PieceColor::Black
+            } else {
+                PieceColor::White
+            };
+
+            *board.get_mut(x)?.get_mut(y)? = Some(Piece::new(piece_type, color));
+
+            x += 1;
+        }
+
+        if x != BOARD_WIDTH {
+            return None;
+        }
+    }
+
+    Some(board)
+}
+
+fn get_castling_pieces(right: char) -> Option<(PieceColor, [Square; 2])> {
+    let (color, y) = if right.is_uppercase() {
+        (PieceColor::White, BOARD_HEIGHT - 1)
+    } else {
+        (PieceColor::Black, 0)
+    };
+
+    let rook_x = match right.to_ascii_lowercase() {
+        'k' => BOARD_WIDTH - 1,
+        'q' => 0,
+        _ => return None,
+    };
+
+    Some((color, [Square::new(4, y), Square::new(rook_x, y)]))
+}
+
+// whether the king and the rook of the castling right are still unmoved on their cells
+pub fn has_castling_right(board: &Board<Option<Piece>>, right: char) -> bool {
+    let Some((color, [king, rook])) = get_castling_pieces(right) else { return false };
+
+    let is_unmoved = |square: Square, piece_type: PieceType| {
+        board[square].is_some_and(|piece| {
+            piece.get_color() == color && piece.get_piece_type() == piece_type && !piece.has_moved()
+        })
+    };
+
+    is_unmoved(king, PieceType::King) && is_unmoved(rook, PieceType::Rook)
+}
+
+// mark the king and the rook of a castling right as unmoved, e.g. 'K' for white's king side
+fn set_castling_right(board: &mut Board<Option<Piece>>, right: char) -> Option<()> {
+    let (color, [king, rook]) = get_castling_pieces(right)?;
+
+    for (square, piece_type) in [(king, PieceType::King), (rook, PieceType::Rook)] {
+        let piece = board[square]
+            .as_mut()
+            .filter(|piece| piece.get_color() == color && piece.get_piece_type() == piece_type)?;
+
+        piece.set_has_moved(false);
+    }
+
+    Some(())
+}
@@ -1,27 +1,118 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use ggez::{event::MouseButton, glam::Vec2, graphics::Image, *};
+use ggez::{
+    event::MouseButton,
+    glam::Vec2,
+    graphics::{Image, Mesh},
+    input::keyboard::{KeyCode, KeyInput},
+    *,
+};
 
-use crate::chess::*;
+use crate::{
+    analysis,
+    chess::*,
+    diagram,
+    game_event::GameEvent,
+    journal::{Journal, JournalEntry},
+    mate_search,
+    move_calculator::Move,
+    piece::{Piece, PieceColor, PieceType},
+    recognizer::{RecognizeError, Recognizer, RECOGNIZER_VAR},
+    ui::Hud,
+    vector_piece,
+    WINDOW_HEIGHT, WINDOW_WIDTH,
+};
+
+// without any input for this long the screen is dimmed and the frame rate lowered
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const IDLE_FRAME_TIME: Duration = Duration::from_millis(100);
+
+// how long a message about the recognizer or the mate search stays on the screen
+const NOTICE_TIME: Duration = Duration::from_secs(4);
+
+// how many moves of the side to move the mate search looks ahead with F.
+// the number keys up to MAX_MATE_SEARCH_MOVES search that many moves instead,
+// any deeper takes minutes.
+const MATE_SEARCH_MOVES: u32 = 3;
+const MAX_MATE_SEARCH_MOVES: u32 = 4;
+
+// how far the mouse has to move while held down before it counts as a drag
+const DRAG_THRESHOLD: f32 = 4.0;
 
 pub struct Assets {
     images: HashMap<String, Image>,
+    // vector fallbacks of the piece sprites that couldn't be loaded
+    piece_meshes: HashMap<String, Mesh>,
+}
+
+// what a piece is drawn with
+pub enum PieceSprite<'a> {
+    Image(&'a Image),
+    Mesh(&'a Mesh),
 }
 
 impl Assets {
-    fn new(_ctx: &mut Context) -> Assets {
-        let images = HashMap::new();
-        Assets { images }
+    fn new() -> Assets {
+        Assets {
+            images: HashMap::new(),
+            piece_meshes: HashMap::new(),
+        }
     }
 
-    pub fn try_get_image(&mut self, ctx: &mut Context, key: &String) -> GameResult<&Image> {
-        if !self.images.contains_key(key) {
-            let path = format!("/{key}.png");
-            self.images
-                .insert(key.clone(), Image::from_path(ctx, path)?);
+    // load every piece sprite up front, building vector shapes for the ones that fail
+    fn preload(&mut self, ctx: &mut Context) -> GameResult {
+        let piece_types = [
+            PieceType::Pawn,
+            PieceType::Rook,
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Queen,
+            PieceType::King,
+        ];
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            for piece_type in piece_types {
+                let piece = Piece::new(piece_type, color);
+                let key = piece.get_sprite_name();
+
+                match Image::from_path(ctx, format!("/{key}.png")) {
+                    Ok(image) => {
+                        self.images.insert(key, image);
+                    }
+                    Err(err) => {
+                        println!("cannot load {key}.png, drawing it with shapes instead: {err}");
+
+                        let mesh = vector_piece::build_piece_mesh(ctx, &piece)?;
+                        self.piece_meshes.insert(key, mesh);
+                    }
+                }
+            }
         }
 
-        Ok(self.images.get(key).expect("cannot load the image"))
+        Ok(())
+    }
+
+    pub fn get_piece_sprite(&self, piece: &Piece) -> PieceSprite<'_> {
+        let key = piece.get_sprite_name();
+
+        if let Some(image) = self.images.get(&key) {
+            return PieceSprite::Image(image);
+        }
+
+        let mesh = self.piece_meshes.get(&key).expect("the assets should be preloaded");
+
+        PieceSprite::Mesh(mesh)
     }
 }
 
@@ -31,6 +122,11 @@ pub struct Mouse {
     is_mouse_down: HashMap<MouseButton, bool>,
     is_mouse_pressed: HashMap<MouseButton, bool>,
     is_mouse_released: HashMap<MouseButton, bool>,
+
+    // where each held button was pressed, kept until the frame it is released
+    drag_start: HashMap<MouseButton, Vec2>,
+    // wheel movement since the last frame
+    wheel: Vec2,
 }
 
 impl Mouse {
@@ -39,9 +135,15 @@ impl Mouse {
             *val = false;
         }
 
-        for (_key, val) in self.is_mouse_released.iter_mut() {
+        for (key, val) in self.is_mouse_released.iter_mut() {
+            if *val {
+                self.drag_start.remove(key);
+            }
+
             *val = false;
         }
+
+        self.wheel = Vec2::ZERO;
     }
 
     pub fn get_mouse(&self) -> Vec2 {
@@ -59,51 +161,603 @@ impl Mouse {
     pub fn is_mouse_released(&self, mouse_button: MouseButton) -> bool {
         *self.is_mouse_released.get(&mouse_button).unwrap_or(&false)
     }
+
+    pub fn get_drag_start(&self, mouse_button: MouseButton) -> Option<Vec2> {
+        self.drag_start.get(&mouse_button).copied()
+    }
+
+    // how far the mouse moved since the button was pressed
+    pub fn get_drag_delta(&self, mouse_button: MouseButton) -> Option<Vec2> {
+        self.get_drag_start(mouse_button).map(|start| self.position - start)
+    }
+
+    // true while the button is held and moved, and on the frame it is released
+    pub fn is_dragging(&self, mouse_button: MouseButton) -> bool {
+        self.get_drag_delta(mouse_button)
+            .is_some_and(|delta| delta.length() >= DRAG_THRESHOLD)
+    }
+
+    // positive y is scrolling up, positive x is scrolling right
+    pub fn get_wheel(&self) -> Vec2 {
+        self.wheel
+    }
+}
+
+#[derive(Default)]
+pub struct Keyboard {
+    is_key_down: HashMap<KeyCode, bool>,
+    is_key_pressed: HashMap<KeyCode, bool>,
+}
+
+impl Keyboard {
+    fn update(&mut self) {
+        for (_key, val) in self.is_key_pressed.iter_mut() {
+            *val = false;
+        }
+    }
+
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        *self.is_key_down.get(&key).unwrap_or(&false)
+    }
+
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        *self.is_key_pressed.get(&key).unwrap_or(&false)
+    }
+
+    pub fn is_ctrl_down(&self) -> bool {
+        self.is_key_down(KeyCode::LControl) || self.is_key_down(KeyCode::RControl)
+    }
+
+    pub fn is_shift_down(&self) -> bool {
+        self.is_key_down(KeyCode::LShift) || self.is_key_down(KeyCode::RShift)
+    }
+}
+
+// a mate search running on its own thread
+struct MateSearch {
+    line: Receiver<Option<Vec<Move>>>,
+    // how many moves it looks ahead
+    moves: u32,
+    // the searched position, the result means nothing once the board shows another one
+    fen: String,
+    // tells the thread to give up when the result isn't wanted anymore
+    abort: Arc<AtomicBool>,
+}
+
+// assets are loaded after the first frame, so that there's a loading screen to look at
+#[derive(PartialEq)]
+enum Loading {
+    NotShown,
+    Shown,
+    Done,
 }
 
 pub struct MainState {
-    screen_width: f32,
-    screen_height: f32,
     board: Chess,
     assets: Assets,
+    hud: Hud,
+    loading: Loading,
     mouse: Mouse,
+    keyboard: Keyboard,
+
+    // last title set on the window, so it is only changed when the game state does
+    window_title: String,
+
+    last_input: Instant,
+    // games are hot-seat without a clock, so the whole game pauses while the window is unfocused
+    is_focused: bool,
+
+    // the last frame stays on the screen until something invalidates it
+    needs_redraw: bool,
+    // whether the last drawn frame was dimmed for being idle
+    is_idle_drawn: bool,
+    // when the last frame was drawn, to hold animations to the frame rate of the graphics quality
+    last_drawn: Instant,
+
+    journal: Journal,
+    // moves of an unfinished game found on startup, until the player decides to recover it
+    recovery: Option<Vec<JournalEntry>>,
+
+    // where the screenshot for the position recognizer is expected
+    screenshot_path: PathBuf,
+    // result of the recognizer while it's running
+    recognition: Option<Receiver<Result<String, RecognizeError>>>,
+
+    // the last message for the player, and when it was shown
+    notice: Option<(String, Instant)>,
+
+    mate_search: Option<MateSearch>,
 }
 
 impl MainState {
     pub fn new(ctx: &mut Context) -> GameResult<MainState> {
-        let (screen_width, screen_height) = ctx.gfx.drawable_size();
-
         let board_position = Vec2::new(80.0, 80.0);
         let board = Chess::new(board_position).init();
 
-        let assets = Assets::new(ctx);
+        let assets = Assets::new();
         let mouse = Default::default();
+        let keyboard = Default::default();
+
+        let journal = Journal::new(ctx.fs.user_data_dir().join("journal.txt"));
+        let recovery = journal.read();
+
+        let screenshot_path = ctx.fs.user_data_dir().join("screenshot.png");
 
         Ok(MainState {
-            screen_width,
-            screen_height,
             board,
             assets,
+            hud: Default::default(),
+            loading: Loading::NotShown,
             mouse,
+            keyboard,
+
+            window_title: String::new(),
+
+            last_input: Instant::now(),
+            is_focused: true,
+
+            needs_redraw: true,
+            is_idle_drawn: false,
+            last_drawn: Instant::now(),
+
+            journal,
+            recovery,
+
+            screenshot_path,
+            recognition: None,
+
+            notice: None,
+
+            mate_search: None,
         })
     }
+
+    // replay the journaled moves on the fresh board
+    fn recover(&mut self, entries: Vec<JournalEntry>) {
+        let mut entries = entries.into_iter().peekable();
+
+        while let Some(entry) = entries.next() {
+            if let JournalEntry::Start(fen) = &entry {
+                if !self.board.restart_from_fen(fen) {
+                    println!("cannot recover the position {fen}, stopping here");
+                    break;
+                }
+            }
+
+            let JournalEntry::Move { from, to } = entry else { continue };
+
+            let promote_to = match entries.peek() {
+                Some(&JournalEntry::Promotion(piece_type)) => Some(piece_type),
+                _ => None,
+            };
+
+            if let Err(err) = self.board.apply_move(from, to, promote_to) {
+                println!("cannot recover the move {from} {to}: {err}, stopping here");
+                break;
+            }
+        }
+
+        // the recovered moves are already in the journal
+        self.board.take_events();
+    }
+
+    fn update_recovery(&mut self) {
+        if self.keyboard.is_key_pressed(KeyCode::Y) {
+            if let Some(entries) = self.recovery.take() {
+                self.recover(entries);
+            }
+        } else if self.keyboard.is_key_pressed(KeyCode::N) {
+            self.recovery = None;
+
+            if let Err(err) = self.journal.clear() {
+                println!("cannot clear the journal: {err}");
+            }
+        }
+    }
+
+    fn write_journal(&self, event: &GameEvent) {
+        let result = match *event {
+            GameEvent::MovePlayed { from, to, .. } => {
+                self.journal.append(JournalEntry::Move { from, to })
+            }
+            GameEvent::Promotion { promote_to, .. } => {
+                self.journal.append(JournalEntry::Promotion(promote_to))
+            }
+            // a finished game has nothing to recover
+            GameEvent::GameEnded(..) => self.journal.clear(),
+            GameEvent::GameStarted { ref fen } => self
+                .journal
+                .clear()
+                .and_then(|_| self.journal.append(JournalEntry::Start(fen.clone()))),
+            _ => Ok(()),
+        };
+
+        if let Err(err) = result {
+            println!("cannot write the journal: {err}");
+        }
+    }
+
+    fn record_input(&mut self) {
+        self.last_input = Instant::now();
+        self.needs_redraw = true;
+    }
+
+    // tell the player something above the board for a while,
+    // the console isn't there for a windowed release build
+    fn show_notice(&mut self, text: impl Into<String>) {
+        self.notice = Some((text.into(), Instant::now()));
+        self.needs_redraw = true;
+    }
+
+    // continue looking at the position on the lichess analysis board
+    fn open_analysis(&mut self) {
+        let url = analysis::get_lichess_url(&self.board.get_fen());
+
+        match analysis::open_in_browser(&url) {
+            Ok(()) => self.show_notice("Opened the position on lichess"),
+            Err(err) => {
+                // the whole url is wider than the window, so the position gets a line of its own
+                let url = url.rsplit_once('/').map_or(url.clone(), |(page, position)| {
+                    format!("{page}/\n{position}")
+                });
+
+                let text = format!("Cannot open the browser: {err}\nThe analysis is at\n{url}");
+                self.show_notice(text);
+            }
+        }
+    }
+
+    // save the position as an svg diagram next to the journal, without overwriting earlier ones
+    fn export_diagram(&mut self, ctx: &Context) {
+        let svg = diagram::build_svg(&self.board.get_snapshot(), &self.board.get_status());
+
+        let dir = ctx.fs.user_data_dir();
+
+        let path = (1..)
+            .map(|n| dir.join(format!("diagram-{n}.svg")))
+            .find(|path| !path.exists())
+            .expect("there should be a free diagram name");
+
+        match fs::create_dir_all(dir).and_then(|_| fs::write(&path, svg)) {
+            Ok(()) => self.show_notice(format!("Saved the diagram to\n{}", path.display())),
+            Err(err) => self.show_notice(format!("Cannot save the diagram:\n{err}")),
+        }
+    }
+
+    fn start_mate_search(&mut self, moves: u32, checks_only: bool) {
+        // a new search replaces the running one
+        self.stop_mate_search();
+
+        // the position is only complete once the piece is chosen
+        if self.board.is_promotion_pending() {
+            self.show_notice("Choose the piece to promote to first");
+            return;
+        }
+
+        let lines = match (checks_only, moves) {
+            (true, _) => "checking moves",
+            (false, 1) => "move",
+            (false, _) => "moves",
+        };
+
+        self.show_notice(format!("Searching for a mate in up to {moves} {lines}..."));
+
+        let game = self.board.clone();
+        let (sender, receiver) = mpsc::channel();
+
+        let abort = Arc::new(AtomicBool::new(false));
+        let thread_abort = abort.clone();
+
+        thread::spawn(move || {
+            let line = mate_search::find_mate(&game, moves, checks_only, &thread_abort);
+
+            // nobody is waiting anymore if the receiver is gone
+            let _ = sender.send(line);
+        });
+
+        self.mate_search = Some(MateSearch {
+            line: receiver,
+            moves,
+            fen: self.board.get_fen(),
+            abort,
+        });
+    }
+
+    fn stop_mate_search(&mut self) {
+        if let Some(search) = self.mate_search.take() {
+            search.abort.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn update_mate_search(&mut self) {
+        let Some(search) = &self.mate_search else { return };
+        let moves = search.moves;
+
+        // a move, a restart or an import changed the board while searching
+        if search.fen != self.board.get_fen() {
+            self.stop_mate_search();
+            self.show_notice("The position changed, so the mate search stopped");
+            return;
+        }
+
+        let line = match search.line.try_recv() {
+            Ok(line) => line,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.show_notice("The mate search stopped without a result");
+                self.mate_search = None;
+                return;
+            }
+        };
+
+        self.mate_search = None;
+
+        let Some(line) = line else {
+            let unit = if moves == 1 { "move" } else { "moves" };
+
+            self.show_notice(format!("There is no mate in {moves} {unit}"));
+            return;
+        };
+
+        let line_text: Vec<String> = line
+            .iter()
+            .map(|mate_move| format!("{}{}", mate_move.from, mate_move.to))
+            .collect();
+
+        // the line starts and ends with a move of the mating side
+        let mate_in = line.len().div_ceil(2);
+
+        self.show_notice(format!("Mate in {mate_in}: {}", line_text.join(" ")));
+    }
+
+    // read the position off the screenshot with the external recognizer, if there's one
+    fn start_recognition(&mut self) {
+        if self.recognition.is_some() {
+            return;
+        }
+
+        let Some(recognizer) = Recognizer::from_env() else {
+            self.show_notice(format!("Set {RECOGNIZER_VAR} to import positions"));
+            return;
+        };
+
+        if !self.screenshot_path.exists() {
+            let path = self.screenshot_path.display();
+
+            self.show_notice(format!("Put the screenshot to import at\n{path}"));
+            return;
+        }
+
+        self.show_notice("Reading the position off the screenshot...");
+
+        self.recognition = Some(recognizer.spawn(self.screenshot_path.clone()));
+    }
+
+    fn update_recognition(&mut self) {
+        let Some(recognition) = &self.recognition else { return };
+
+        let result = match recognition.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.show_notice("The recognizer stopped without a result");
+                self.recognition = None;
+                return;
+            }
+        };
+
+        self.recognition = None;
+
+        match result {
+            Ok(fen) => {
+                // the fen is already known to be legal
+                self.board.restart_from_fen(&fen);
+                self.show_notice("Imported the position");
+            }
+            Err(err) => self.show_notice(format!("Cannot import the position:\n{err}")),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.last_input.elapsed() >= IDLE_TIMEOUT
+    }
 }
 
 impl ggez::event::EventHandler<GameError> for MainState {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if self.loading != Loading::Done {
+            if self.loading == Loading::Shown {
+                self.assets.preload(ctx)?;
+                self.loading = Loading::Done;
+            }
+
+            // input made before the game shows up is ignored
+            self.mouse.update();
+            self.keyboard.update();
+
+            return Ok(());
+        }
+
+        if !self.is_focused {
+            thread::sleep(IDLE_FRAME_TIME);
+
+            // input doesn't reach the paused game
+            self.mouse.update();
+            self.keyboard.update();
+
+            return Ok(());
+        }
+
+        if self.is_idle() {
+            // nothing changes without input, so there's no need to run at full speed
+            thread::sleep(IDLE_FRAME_TIME);
+        }
+
+        let was_animating = self.board.is_animating();
+
         // update things here:
-        self.board.update(&self.mouse);
+        if self.recovery.is_some() {
+            // the board waits until the player decides what to do with the unfinished game
+            self.update_recovery();
+        } else {
+            self.board.update(&self.mouse, &self.keyboard);
+        }
+
+        if self.keyboard.is_key_pressed(KeyCode::O) {
+            self.open_analysis();
+        }
+
+        if self.keyboard.is_key_pressed(KeyCode::D) {
+            self.export_diagram(ctx);
+        }
+
+        // shift only follows checks, to look further ahead quickly
+        let checks_only = self.keyboard.is_shift_down();
+
+        if self.keyboard.is_key_pressed(KeyCode::F) {
+            self.start_mate_search(MATE_SEARCH_MOVES, checks_only);
+        }
+
+        let number_keys = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+        ];
 
-        // update mouse at the last moment
+        for (moves, key) in (1..=MAX_MATE_SEARCH_MOVES).zip(number_keys) {
+            if self.keyboard.is_key_pressed(key) {
+                self.start_mate_search(moves, checks_only);
+            }
+        }
+
+        self.update_mate_search();
+
+        // importing a position throws the game away, so it waits for the recovery question too
+        if self.recovery.is_none() && self.keyboard.is_key_pressed(KeyCode::I) {
+            self.start_recognition();
+        }
+
+        self.update_recognition();
+
+        // the notice of a search or an import that's still running stays until its result
+        let is_running = self.mate_search.is_some() || self.recognition.is_some();
+
+        let is_notice_expired = !is_running
+            && self
+                .notice
+                .as_ref()
+                .is_some_and(|(_, shown_at)| shown_at.elapsed() >= NOTICE_TIME);
+
+        if is_notice_expired {
+            self.notice = None;
+            self.needs_redraw = true;
+        }
+
+        for event in self.board.take_events() {
+            self.write_journal(&event);
+
+            self.needs_redraw = true;
+        }
+
+        if self.board.take_changed() {
+            self.needs_redraw = true;
+        }
+
+        let is_animating = self.board.is_animating();
+
+        let is_frame_due = self
+            .board
+            .get_graphics_quality()
+            .get_frame_time()
+            .is_none_or(|frame_time| self.last_drawn.elapsed() >= frame_time);
+
+        // one more frame is drawn after an animation stops, so that its last state is cleared.
+        // an idle game holds its animations still until the next input
+        let is_animation_due = (is_animating && is_frame_due) || (was_animating && !is_animating);
+
+        if (is_animation_due && !self.is_idle()) || self.is_idle() != self.is_idle_drawn {
+            self.needs_redraw = true;
+        }
+
+        let window_title = format!("rust-chess — {}", self.board.get_status());
+
+        if window_title != self.window_title {
+            ctx.gfx.set_window_title(&window_title);
+            self.window_title = window_title;
+        }
+
+        // update input at the last moment
         self.mouse.update();
+        self.keyboard.update();
 
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        // ggez keeps presenting the last frame, so a static board costs nothing to show
+        if self.loading == Loading::Done && !self.needs_redraw {
+            return Ok(());
+        }
+
         let mut canvas = graphics::Canvas::from_frame(ctx, graphics::Color::WHITE);
 
-        self.board.draw(ctx, &mut canvas, &mut self.assets)?;
+        if self.loading != Loading::Done {
+            let text = graphics::Text::new("Loading...")
+                .set_scale(32.)
+                .set_layout(graphics::TextLayout {
+                    h_align: graphics::TextAlign::Middle,
+                    v_align: graphics::TextAlign::Middle,
+                })
+                .clone();
+
+            canvas.draw(
+                &text,
+                graphics::DrawParam::from(Vec2::new(WINDOW_WIDTH / 2.0, WINDOW_HEIGHT / 2.0))
+                    .color(graphics::Color::BLACK),
+            );
+
+            self.loading = Loading::Shown;
+
+            return canvas.finish(ctx);
+        }
+
+        let notice = self.notice.as_ref().map(|(text, _)| text.as_str());
+
+        self.board.draw(ctx, &mut canvas, &self.assets, &mut self.hud, notice)?;
+
+        if let Some(entries) = &self.recovery {
+            let moves = entries
+                .iter()
+                .filter(|entry| matches!(entry, JournalEntry::Move { .. }))
+                .count();
+
+            let text = format!(
+                "An unfinished game with {moves} moves was found.\n\n\
+                 Press Y to continue it or N to start a new game"
+            );
+
+            Chess::draw_popup(&mut canvas, &text, Vec2::new(480.0, 160.0));
+        }
+
+        self.needs_redraw = false;
+        self.is_idle_drawn = self.is_idle();
+        self.last_drawn = Instant::now();
+
+        if self.is_idle_drawn {
+            canvas.draw(
+                &graphics::Quad,
+                graphics::DrawParam::default()
+                    .scale([WINDOW_WIDTH, WINDOW_HEIGHT])
+                    .color(graphics::Color::from_rgba_u32(0x00000099)),
+            );
+        }
+
+        if !self.is_focused {
+            let text = "Paused\n\nClick the window to continue";
+
+            Chess::draw_popup(&mut canvas, text, Vec2::new(320.0, 120.0));
+        }
 
         canvas.finish(ctx)?;
 
@@ -117,8 +771,11 @@ impl ggez::event::EventHandler<GameError> for MainState {
         _x: f32,
         _y: f32,
     ) -> GameResult {
+        self.record_input();
+
         self.mouse.is_mouse_down.insert(button, true);
         self.mouse.is_mouse_pressed.insert(button, true);
+        self.mouse.drag_start.insert(button, self.mouse.position);
         // println!("Mouse button pressed: {button:?}, x: {x}, y: {y}");
 
         Ok(())
@@ -131,12 +788,49 @@ impl ggez::event::EventHandler<GameError> for MainState {
         _x: f32,
         _y: f32,
     ) -> GameResult {
+        self.record_input();
+
         self.mouse.is_mouse_down.insert(button, false);
+        self.mouse.is_mouse_released.insert(button, true);
         // println!("Mouse button released: {button:?}, x: {x}, y: {y}");
 
         Ok(())
     }
 
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        input: KeyInput,
+        repeated: bool,
+    ) -> GameResult {
+        self.record_input();
+
+        let Some(keycode) = input.keycode else { return Ok(()) };
+
+        // keep ggez's default behavior of quitting on escape
+        if keycode == KeyCode::Escape {
+            ctx.request_quit();
+        }
+
+        self.keyboard.is_key_down.insert(keycode, true);
+
+        if !repeated {
+            self.keyboard.is_key_pressed.insert(keycode, true);
+        }
+
+        Ok(())
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, input: KeyInput) -> GameResult {
+        self.record_input();
+
+        let Some(keycode) = input.keycode else { return Ok(()) };
+
+        self.keyboard.is_key_down.insert(keycode, false);
+
+        Ok(())
+    }
+
     fn mouse_motion_event(
         &mut self,
         _ctx: &mut Context,
@@ -145,6 +839,8 @@ impl ggez::event::EventHandler<GameError> for MainState {
         _xrel: f32,
         _yrel: f32,
     ) -> GameResult {
+        self.record_input();
+
         self.mouse.position.x = x;
         self.mouse.position.y = y;
 
@@ -159,4 +855,19 @@ impl ggez::event::EventHandler<GameError> for MainState {
         // println!("Mouse motion, x: {x}, y: {y}, relative x: {xrel}, relative y: {yrel}");
         Ok(())
     }
+
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) -> GameResult {
+        self.is_focused = gained;
+        self.needs_redraw = true;
+
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, x: f32, y: f32) -> GameResult {
+        self.record_input();
+
+        self.mouse.wheel += Vec2::new(x, y);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use crate::{piece::*, square::Square};
+
+// things that happened in the game, queued by Chess for other layers (ui, audio, logging) to consume
+#[derive(Clone)]
+pub enum GameEvent {
+    MovePlayed {
+        piece: Piece,
+        from: Square,
+        to: Square,
+    },
+    Capture {
+        captured: Piece,
+        cell: Square,
+    },
+    Check {
+        color: PieceColor,
+    },
+    Promotion {
+        color: PieceColor,
+        cell: Square,
+        promote_to: PieceType,
+    },
+    GameEnded(GameEnd),
+    // the game was thrown away for a new one from the given position
+    GameStarted {
+        fen: String,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameEnd {
+    Checkmate { winner: PieceColor },
+    Stalemate,
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use GameEvent::*;
+
+        match self {
+            MovePlayed { piece, from, to } => write!(
+                f,
+                "{} {} {} to {}",
+                piece.get_color().get_name(),
+                piece.get_piece_type().get_name(),
+                from,
+                to,
+            ),
+            Capture { captured, cell } => write!(
+                f,
+                "{} {} captured on {}",
+                captured.get_color().get_name(),
+                captured.get_piece_type().get_name(),
+                cell,
+            ),
+            Check { color } => write!(f, "{} is in check", color.get_name()),
+            Promotion {
+                color,
+                cell,
+                promote_to,
+            } => write!(
+                f,
+                "{} promotes to {} on {}",
+                color.get_name(),
+                promote_to.get_name(),
+                cell,
+            ),
+            GameEnded(GameEnd::Checkmate { winner }) => {
+                write!(f, "Checkmate, {} wins", winner.get_name())
+            }
+            GameEnded(GameEnd::Stalemate) => write!(f, "Stalemate, the game is drawn"),
+            GameStarted { fen } => write!(f, "New game from {fen}"),
+        }
+    }
+}
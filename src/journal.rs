@@ -0,0 +1,88 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::{piece::PieceType, square::Square};
+
+// append-only record of the game in progress, written as each move is played
+// so that a crash loses at most the current selection
+pub struct Journal {
+    path: PathBuf,
+}
+
+#[derive(Clone)]
+pub enum JournalEntry {
+    // the position the game started from, when it's not the standard one
+    Start(String),
+    Move { from: Square, to: Square },
+    Promotion(PieceType),
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Journal { path }
+    }
+
+    pub fn append(&self, entry: JournalEntry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+
+        match entry {
+            JournalEntry::Start(fen) => writeln!(file, "start {fen}")?,
+            JournalEntry::Move { from, to } => writeln!(file, "move {from} {to}")?,
+            JournalEntry::Promotion(piece_type) => writeln!(file, "promote {piece_type}")?,
+        }
+
+        // make sure the line is on the disk before the next move can be played
+        file.sync_data()
+    }
+
+    // entries left behind by a game that didn't finish, None if there are none.
+    // reading stops at the first line that can't be parsed, e.g. one cut off by a crash.
+    pub fn read(&self) -> Option<Vec<JournalEntry>> {
+        let content = fs::read_to_string(&self.path).ok()?;
+
+        let entries: Vec<JournalEntry> = content.lines().map_while(Journal::parse_line).collect();
+
+        (!entries.is_empty()).then_some(entries)
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<JournalEntry> {
+        if let Some(fen) = line.strip_prefix("start ") {
+            return Some(JournalEntry::Start(fen.to_string()));
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words[..] {
+            ["move", from, to] => Some(JournalEntry::Move {
+                from: Square::from_algebraic(from)?,
+                to: Square::from_algebraic(to)?,
+            }),
+            ["promote", piece_type] => {
+                let piece_type = match piece_type {
+                    "q" => PieceType::Queen,
+                    "r" => PieceType::Rook,
+                    "b" => PieceType::Bishop,
+                    "n" => PieceType::Knight,
+                    _ => return None,
+                };
+
+                Some(JournalEntry::Promotion(piece_type))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Chess rules and a ggez frontend.
+//!
+//! `Chess` holds the game state and computes the legal moves of the side to move.
+//! `move_calculator` generates pseudo-legal moves and attacks for any board,
+//! and `Square` addresses the cells of a board.
+//! The binary opens a window and runs `game::MainState`,
+//! or renders diagrams of a game with its `diagrams` subcommand.
+
+pub mod analysis;
+pub mod chess;
+pub mod diagram;
+pub mod fen;
+pub mod game;
+pub mod game_event;
+pub mod journal;
+pub mod mate_search;
+pub mod middlegame;
+pub mod move_calculator;
+pub mod pgn;
+pub mod piece;
+pub mod recognizer;
+pub mod snapshot;
+pub mod square;
+pub mod ui;
+pub mod vector_piece;
+
+pub use crate::chess::{Board, Chess};
+pub use move_calculator::Move;
+pub use square::Square;
+
+pub const WINDOW_WIDTH: f32 = 800.0;
+pub const WINDOW_HEIGHT: f32 = 800.0;
@@ -1,17 +1,19 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod chess;
-mod game;
-mod move_calculator;
-mod piece;
+use std::{env, fs, path::Path, process};
 
-use game::MainState;
+use chess::{diagram, game::MainState, WINDOW_HEIGHT, WINDOW_WIDTH};
 use ggez::*;
 
-const WINDOW_WIDTH: f32 = 800.0;
-const WINDOW_HEIGHT: f32 = 800.0;
+const USAGE: &str = "usage: rust-chess diagrams <game.pgn> <output dir> <move>...\n\
+                     where a move is 12 for white's twelfth move or 12... for black's";
 
 fn main() -> GameResult {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("diagrams") {
+        render_diagrams(&args[1..]);
+    }
     // We add the CARGO_MANIFEST_DIR/resources to the resource paths
     // so that ggez will look in our cargo project directory for files.
     // let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
@@ -23,15 +25,48 @@ fn main() -> GameResult {
     // };
 
     let c = conf::Conf::new();
-    let (mut ctx, event_loop) = ContextBuilder::new("rust_chess", "cdh981009")
+    let window_setup = conf::WindowSetup::default().title("rust-chess");
+
+    let builder = ContextBuilder::new("rust_chess", "cdh981009")
         .default_conf(c)
-        .add_zipfile_bytes(include_bytes!("../resources.zip").to_vec())
         //.add_resource_path(resource_dir)
-        .window_mode(conf::WindowMode::default().dimensions(WINDOW_WIDTH, WINDOW_HEIGHT))
-        .build()
-        .unwrap();
+        .window_mode(conf::WindowMode::default().dimensions(WINDOW_WIDTH, WINDOW_HEIGHT));
+
+    #[cfg(feature = "resources")]
+    let builder = builder
+        .add_zipfile_bytes(include_bytes!("../resources.zip").to_vec())
+        .window_setup(window_setup.icon("/wk.png"));
+
+    #[cfg(not(feature = "resources"))]
+    let builder = builder.window_setup(window_setup);
+
+    let (mut ctx, event_loop) = builder.build().unwrap();
 
     let state = MainState::new(&mut ctx)?;
 
     event::run(ctx, event_loop, state);
 }
+
+// the diagrams subcommand, which renders positions of a game without opening a window
+fn render_diagrams(args: &[String]) -> ! {
+    let [pgn_path, dir, move_numbers @ ..] = args else {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    };
+
+    if move_numbers.is_empty() {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    }
+
+    let result = fs::read_to_string(pgn_path)
+        .map_err(|err| format!("cannot read {pgn_path}: {err}"))
+        .and_then(|pgn| diagram::render_pgn(&pgn, Path::new(dir), move_numbers));
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+
+    process::exit(0);
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{chess::Chess, move_calculator::Move};
+
+// the shortest forced mate for the side to move within the given number of its moves,
+// as the line of moves of both sides, or None if there is no such mate.
+// the defender plays the reply that holds out the longest.
+// with checks_only, the mating side only gives check, which finds long forcing mates quickly
+// but misses the ones with a quiet move.
+// promotions are only tried to a queen, like everywhere a move is simulated.
+// setting abort stops the search early, and it returns None.
+pub fn find_mate(
+    game: &Chess,
+    moves: u32,
+    checks_only: bool,
+    abort: &AtomicBool,
+) -> Option<Vec<Move>> {
+    (1..=moves).find_map(|depth| search(game, depth, checks_only, abort))
+}
+
+// depth-first search for a mate in exactly `depth` moves or less
+fn search(game: &Chess, depth: u32, checks_only: bool, abort: &AtomicBool) -> Option<Vec<Move>> {
+    if abort.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let mut candidates: Vec<(Move, Chess)> = game
+        .get_legal_moves()
+        .iter()
+        .filter_map(|attack| Some((*attack, game.simulate_move(attack).ok()?)))
+        .collect();
+
+    // a mating move always gives check, so the last move only needs to try checks
+    if checks_only || depth == 1 {
+        candidates.retain(|(_, after)| after.is_in_check(after.get_turn_color()));
+    }
+
+    // checks are the most forcing moves, so they're tried first
+    candidates.sort_by_key(|(_, after)| !after.is_in_check(after.get_turn_color()));
+
+    for (attack, after) in candidates {
+        if after.is_checkmate() {
+            return Some(vec![attack]);
+        }
+
+        // no more moves to mate with, or stalemate
+        if depth == 1 || after.get_legal_moves().is_empty() {
+            continue;
+        }
+
+        let mut longest: Option<Vec<Move>> = None;
+
+        for defence in after.get_legal_moves() {
+            let reply = after.simulate_move(defence).ok();
+
+            let line = reply.and_then(|reply| search(&reply, depth - 1, checks_only, abort));
+
+            let Some(line) = line else {
+                // this reply escapes the mate
+                longest = None;
+                break;
+            };
+
+            if longest.as_ref().is_none_or(|longest| line.len() + 1 > longest.len()) {
+                longest = Some([vec![*defence], line].concat());
+            }
+        }
+
+        if let Some(line) = longest {
+            return Some([vec![attack], line].concat());
+        }
+    }
+
+    None
+}
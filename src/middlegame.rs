@@ -0,0 +1,31 @@
+use rand::seq::SliceRandom;
+
+// balanced positions out of common openings, to practice middlegames without the theory
+pub const MIDDLEGAMES: &[&str] = &[
+    // ruy lopez, closed
+    "r1bq1rk1/2p1bppp/p1np1n2/1p2p3/4P3/1BP2N1P/PP1P1PP1/RNBQR1K1 b - - 0 9",
+    // queen's gambit declined, orthodox
+    "r1bq1rk1/pp1nbppp/2p1pn2/6B1/2BP4/2N1PN2/PP3PPP/2RQK2R b K - 0 9",
+    // sicilian najdorf, english attack
+    "rn1q1rk1/1p2bppp/p2pbn2/4p3/4P3/1NN1BP2/PPPQ2PP/R3KB1R w KQ - 3 10",
+    // italian, giuoco pianissimo
+    "r1bq1rk1/bpp2ppp/p1np1n2/4p3/4P3/1BPP1N1P/PP3PP1/RNBQR1K1 b - - 0 9",
+    // king's indian, classical
+    "r1bq1rk1/ppp1npbp/3p1np1/3Pp3/2P1P3/2N2N2/PP2BPPP/R1BQ1RK1 w - - 1 9",
+    // caro-kann, classical
+    "r2qkb1r/pp1n1pp1/2p1pn1p/7P/3P4/3Q1NN1/PPPB1PP1/2KR3R b kq - 3 12",
+    // french, tarrasch
+    "r1bqk2r/pp4pp/2nbpn2/3p4/3P4/3B4/PP1NNPPP/R1BQ1RK1 w kq - 2 11",
+    // english, symmetrical
+    "r1bq1rk1/pp1pnpbp/2n1p1p1/2p5/2P5/2NP1NP1/PP2PPBP/R1BQ1RK1 w - - 1 8",
+    // slav, main line
+    "r2q1rk1/pp1n1ppp/2p1pn2/5b2/PbBP4/2N1PN2/1P2QPPP/R1B2RK1 w - - 5 10",
+    // nimzo-indian, rubinstein
+    "r1b2rk1/ppq2ppp/2n1pn2/2pp4/2PP4/P1PBPN2/5PPP/R1BQ1RK1 w - - 1 10",
+];
+
+pub fn pick_random() -> &'static str {
+    MIDDLEGAMES
+        .choose(&mut rand::thread_rng())
+        .expect("there should be a middlegame to pick")
+}
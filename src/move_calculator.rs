@@ -1,189 +1,391 @@
 use crate::chess::{Board, Chess, BOARD_HEIGHT, BOARD_WIDTH};
-use crate::game::Assets;
 use crate::piece::*;
+use crate::square::Square;
+
+const KNIGHT_DIRS: [(i32, i32); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (2, -1),
+    (2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+];
+
+const KING_DIRS: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+const ORTHOGONAL_DIRS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+const DIAGONAL_DIRS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum MoveKind {
+    Normal,
+    Capture,
+    EnPassant,
+    Castling,
+}
 
-pub fn get_pseudo_legal_moves(board: &Board<Option<Piece>>, ind: (usize, usize), moves: &mut Board<bool>) {
-    let Some(piece) = &board[ind.0][ind.1] else { return };
-
-    use PieceType::*;
-    match piece.get_piece_type() {
-        Pawn { .. } => get_pawn_moves(piece, board, ind, moves),
-        Knight => get_knight_moves(piece, board, ind, moves),
-        Bishop => get_bishop_moves(piece, board, ind, moves),
-        Rook => get_rook_moves(piece, board, ind, moves),
-        Queen => get_queen_moves(piece, board, ind, moves),
-        King => get_king_moves(piece, board, ind, moves),
-    }
+#[derive(Copy, Clone, PartialEq)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub kind: MoveKind,
+    pub is_promotion: bool,
 }
 
-pub fn get_all_attacks(board: &Board<Option<Piece>>, color: PieceColor) -> Board<bool> {
-    let mut attacks = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+impl Move {
+    // describe moving the piece on `from` to `to`, before the move is made
+    pub fn new(board: &Board<Option<Piece>>, from: Square, to: Square) -> Self {
+        let piece = board[from].expect("the origin of a move should contain a piece");
 
-    for x in 0..BOARD_WIDTH {
-        for y in 0..BOARD_HEIGHT {
-            if !(board[x][y].is_some_and(|piece| piece.get_color() == color)) {
-                continue;
-            }
+        let is_pawn = piece.get_piece_type() == PieceType::Pawn;
+        let is_king = piece.get_piece_type() == PieceType::King;
+
+        let kind = if !Chess::is_empty_on(board, to) {
+            MoveKind::Capture
+        } else if is_pawn && from.x() != to.x() {
+            MoveKind::EnPassant
+        } else if is_king && from.x().abs_diff(to.x()) == 2 {
+            MoveKind::Castling
+        } else {
+            MoveKind::Normal
+        };
+
+        let promotion_row = match piece.get_color() {
+            PieceColor::White => 0,
+            PieceColor::Black => BOARD_HEIGHT - 1,
+        };
 
-            get_pseudo_legal_moves(board, (x, y), &mut attacks);
+        Move {
+            from,
+            to,
+            kind,
+            is_promotion: is_pawn && to.y() == promotion_row,
         }
     }
 
-    attacks
+    pub fn is_capture(&self) -> bool {
+        matches!(self.kind, MoveKind::Capture | MoveKind::EnPassant)
+    }
 }
 
-fn get_pawn_moves(
-    piece: &Piece,
+// declarative description of how a piece moves,
+// so that new (fairy) pieces can be defined as data instead of a new move function
+pub enum MovePattern {
+    // jumps directly to each offset, e.g. knight and king
+    Leaper(&'static [(i32, i32)]),
+    // slides along each direction until it is blocked, e.g. bishop, rook and queen
+    Rider(&'static [(i32, i32)]),
+    // slides along each direction and must jump over exactly one piece,
+    // landing right behind it, e.g. the grasshopper
+    Hopper(&'static [(i32, i32)]),
+}
+
+// pawns are not described here since their moves depend on color, history and captures
+pub fn get_move_patterns(piece_type: PieceType) -> &'static [MovePattern] {
+    use MovePattern::*;
+    use PieceType::*;
+
+    match piece_type {
+        Pawn => &[],
+        Knight => &[Leaper(&KNIGHT_DIRS)],
+        Bishop => &[Rider(&DIAGONAL_DIRS)],
+        Rook => &[Rider(&ORTHOGONAL_DIRS)],
+        Queen => &[Rider(&DIAGONAL_DIRS), Rider(&ORTHOGONAL_DIRS)],
+        King => &[Leaper(&KING_DIRS)],
+    }
+}
+
+// en_passant_target is the cell a pawn skipped with a double step on the last move, if any
+pub fn get_pseudo_legal_moves(
     board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
+    ind: Square,
+    en_passant_target: Option<Square>,
     moves: &mut Board<bool>,
 ) {
-    // pawn move rule:
-    // only can move forward. 2 if it's the first time, 1 otherwise.
-    // must attack diagonally.
+    let Some(piece) = &board[ind] else { return };
 
-    let y_direction = if piece.get_color() == PieceColor::White {
-        -1
-    } else {
-        1
+    match piece.get_piece_type() {
+        PieceType::Pawn => get_pawn_moves(piece, board, ind, en_passant_target, moves),
+        piece_type => {
+            for pattern in get_move_patterns(piece_type) {
+                get_pattern_cells(board, ind, pattern, piece.get_color(), false, moves);
+            }
+
+            if piece_type == PieceType::King {
+                get_castling_moves(piece, board, ind, moves);
+            }
+        }
+    }
+}
+
+// whether a piece of the given color attacks the square, looking outwards from the square
+// instead of generating every move of the attacking side
+pub fn is_square_attacked(board: &Board<Option<Piece>>, square: Square, by: PieceColor) -> bool {
+    let is_attacker_on = |target: Square, is_attacker_type: fn(PieceType) -> bool| {
+        board[target].is_some_and(|piece| {
+            piece.get_color() == by && is_attacker_type(piece.get_piece_type())
+        })
     };
-    let reach = if piece.has_moved() { 1 } else { 2 };
 
-    // move
-    for move_y in 1..=reach {
-        let (nx, ny) = (x as i32, y as i32 + move_y * y_direction);
+    let is_leaped = |offsets: &[(i32, i32)], is_attacker_type: fn(PieceType) -> bool| {
+        offsets
+            .iter()
+            .filter_map(|offset| square.offset(*offset))
+            .any(|target| is_attacker_on(target, is_attacker_type))
+    };
 
-        if !Chess::is_position_in_bound((nx, ny)) {
-            break;
-        }
+    let is_ridden = |dirs: &[(i32, i32)], is_attacker_type: fn(PieceType) -> bool| {
+        dirs.iter().any(|dir| {
+            let mut current = square;
 
-        let ind = (nx as usize, ny as usize);
-        if Chess::is_empty_on(board, ind) {
-            moves[ind.0][ind.1] = true;
-        } else {
-            break;
-        }
-    }
+            // the first piece along the direction is the only one that can attack
+            while let Some(target) = current.offset(*dir) {
+                if !Chess::is_empty_on(board, target) {
+                    return is_attacker_on(target, is_attacker_type);
+                }
 
-    // attack
-    let enemy_color = piece.get_color().get_enemy_color();
+                current = target;
+            }
 
-    for move_x in [-1, 1] {
-        let (nx, ny) = (x as i32 + move_x, y as i32 + y_direction);
+            false
+        })
+    };
+
+    // pawns of the attacking color capture towards the square from behind it
+    let pawn_y = if by == PieceColor::White { 1 } else { -1 };
+
+    is_leaped(&[(-1, pawn_y), (1, pawn_y)], |t| t == PieceType::Pawn)
+        || is_leaped(&KNIGHT_DIRS, |t| t == PieceType::Knight)
+        || is_leaped(&KING_DIRS, |t| t == PieceType::King)
+        || is_ridden(&ORTHOGONAL_DIRS, |t| matches!(t, PieceType::Rook | PieceType::Queen))
+        || is_ridden(&DIAGONAL_DIRS, |t| matches!(t, PieceType::Bishop | PieceType::Queen))
+}
+
+// count how many pieces of the given color attack each cell.
+// cells occupied by the same color are counted too (defended),
+// and pawn pushes and castling are not attacks.
+pub fn get_attack_counts(board: &Board<Option<Piece>>, color: PieceColor) -> Board<u32> {
+    let mut counts = [[0; BOARD_HEIGHT]; BOARD_WIDTH];
+
+    for square in Square::all() {
+        let Some(piece) = &board[square] else { continue };
 
-        if !Chess::is_position_in_bound((nx, ny)) {
+        if piece.get_color() != color {
             continue;
         }
 
-        let ind = (nx as usize, ny as usize);
+        let mut controlled = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+        get_controlled_cells(piece, board, square, &mut controlled);
 
-        let is_directly_attackable = Chess::is_color_on(board, ind, enemy_color);
-        let can_en_passant = board[ind.0][y].is_some_and(|piece| {
-            // check x-adjacent cell
-            matches!(piece.get_piece_type(), PieceType::Pawn { en_passant: true })
-        });
+        let zipped = counts.iter_mut().flatten().zip(controlled.iter().flatten());
 
-        if is_directly_attackable || can_en_passant {
-            moves[ind.0][ind.1] = true;
+        for (count, is_controlled) in zipped {
+            if *is_controlled {
+                *count += 1;
+            }
         }
     }
+
+    counts
 }
 
-fn get_knight_moves(
-    piece: &Piece,
-    board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    moves: &mut Board<bool>,
-) {
-    static DIRS: [(i32, i32); 8] = [
-        (-2, -1),
-        (-2, 1),
-        (2, -1),
-        (2, 1),
-        (-1, -2),
-        (-1, 2),
-        (1, -2),
-        (1, 2),
-    ];
+// the lines along which enemy pieces give check to the king on the given square,
+// each running from the checking piece up to the cell before the king.
+// pieces not in line with the king, like knights, give a line of just their own cell.
+// a double check gives two lines.
+pub fn get_check_rays(board: &Board<Option<Piece>>, king: Square) -> Vec<Vec<Square>> {
+    let Some(king_piece) = &board[king] else { return Vec::new() };
+    let enemy_color = king_piece.get_color().get_enemy_color();
 
-    let enemy_color = piece.get_color().get_enemy_color();
+    let mut rays = Vec::new();
 
-    for (move_x, move_y) in DIRS {
-        let (nx, ny) = (x as i32 + move_x, y as i32 + move_y);
+    for square in Square::all() {
+        let Some(piece) = &board[square] else { continue };
 
-        if !Chess::is_position_in_bound((nx, ny)) {
+        if piece.get_color() != enemy_color {
             continue;
         }
 
-        let ind = (nx as usize, ny as usize);
+        let mut controlled = [[false; BOARD_HEIGHT]; BOARD_WIDTH];
+        get_controlled_cells(piece, board, square, &mut controlled);
 
-        if Chess::is_empty_on(board, ind) || Chess::is_color_on(board, ind, enemy_color) {
-            moves[ind.0][ind.1] = true;
+        if !controlled[king] {
+            continue;
         }
+
+        let dx = king.x() as i32 - square.x() as i32;
+        let dy = king.y() as i32 - square.y() as i32;
+
+        let mut ray = vec![square];
+
+        if dx == 0 || dy == 0 || dx.abs() == dy.abs() {
+            let step = (dx.signum(), dy.signum());
+            let mut current = square;
+
+            while let Some(next) = current.offset(step).filter(|&next| next != king) {
+                ray.push(next);
+                current = next;
+            }
+        }
+
+        rays.push(ray);
     }
+
+    rays
 }
 
-fn get_bishop_moves(
+fn get_controlled_cells(
     piece: &Piece,
     board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    moves: &mut Board<bool>,
+    square: Square,
+    controlled: &mut Board<bool>,
 ) {
-    let enemy_color = piece.get_color().get_enemy_color();
+    let PieceType::Pawn = piece.get_piece_type() else {
+        for pattern in get_move_patterns(piece.get_piece_type()) {
+            get_pattern_cells(board, square, pattern, piece.get_color(), true, controlled);
+        }
 
-    get_diagonal_moves(board, (x, y), enemy_color, moves)
-}
+        return;
+    };
 
-fn get_rook_moves(
-    piece: &Piece,
-    board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    moves: &mut Board<bool>,
-) {
-    let enemy_color = piece.get_color().get_enemy_color();
+    let y_direction = if piece.get_color() == PieceColor::White {
+        -1
+    } else {
+        1
+    };
 
-    get_orthogonal_moves(board, (x, y), enemy_color, moves)
+    for move_x in [-1, 1] {
+        if let Some(target) = square.offset((move_x, y_direction)) {
+            controlled[target] = true;
+        }
+    }
 }
 
-fn get_queen_moves(
-    piece: &Piece,
+// mark the cells the pattern reaches from the square for a piece of the given color.
+// a cell holding a piece of the same color is only marked when computing control.
+pub fn get_pattern_cells(
     board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    moves: &mut Board<bool>,
+    square: Square,
+    pattern: &MovePattern,
+    color: PieceColor,
+    is_control: bool,
+    cells: &mut Board<bool>,
 ) {
-    let enemy_color = piece.get_color().get_enemy_color();
+    let mut try_mark = |target: Square| {
+        if is_control || !Chess::is_color_on(board, target, color) {
+            cells[target] = true;
+        }
+    };
+
+    match pattern {
+        MovePattern::Leaper(offsets) => {
+            for offset in offsets.iter() {
+                if let Some(target) = square.offset(*offset) {
+                    try_mark(target);
+                }
+            }
+        }
+        MovePattern::Rider(dirs) => {
+            for dir in dirs.iter() {
+                let mut current = square;
+
+                while let Some(target) = current.offset(*dir) {
+                    try_mark(target);
+
+                    if !Chess::is_empty_on(board, target) {
+                        break;
+                    }
+
+                    current = target;
+                }
+            }
+        }
+        MovePattern::Hopper(dirs) => {
+            for dir in dirs.iter() {
+                let mut current = square;
+
+                // slide up to the hurdle
+                let hurdle = loop {
+                    let Some(target) = current.offset(*dir) else { break None };
+
+                    if !Chess::is_empty_on(board, target) {
+                        break Some(target);
+                    }
+
+                    current = target;
+                };
 
-    get_diagonal_moves(board, (x, y), enemy_color, moves);
-    get_orthogonal_moves(board, (x, y), enemy_color, moves);
+                // and land right behind it
+                if let Some(target) = hurdle.and_then(|hurdle| hurdle.offset(*dir)) {
+                    try_mark(target);
+                }
+            }
+        }
+    }
 }
 
-fn get_king_moves(
+fn get_pawn_moves(
     piece: &Piece,
     board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
+    square: Square,
+    en_passant_target: Option<Square>,
     moves: &mut Board<bool>,
 ) {
-    let enemy_color = piece.get_color().get_enemy_color();
+    // pawn move rule:
+    // only can move forward. 2 if it's the first time, 1 otherwise.
+    // must attack diagonally.
 
-    for move_x in -1..=1 {
-        for move_y in -1..=1 {
-            if (move_x, move_y) == (0, 0) {
-                continue;
-            }
+    let y_direction = if piece.get_color() == PieceColor::White {
+        -1
+    } else {
+        1
+    };
+    let reach = if piece.has_moved() { 1 } else { 2 };
 
-            let (nx, ny) = (x as i32 + move_x, y as i32 + move_y);
+    // move
+    for move_y in 1..=reach {
+        let Some(target) = square.offset((0, move_y * y_direction)) else { break };
 
-            if !Chess::is_position_in_bound((nx, ny)) {
-                continue;
-            }
-            let ind = (nx as usize, ny as usize);
+        if Chess::is_empty_on(board, target) {
+            moves[target] = true;
+        } else {
+            break;
+        }
+    }
 
-            if Chess::is_empty_on(board, ind) || Chess::is_color_on(board, ind, enemy_color) {
-                moves[ind.0][ind.1] = true;
-            }
+    // attack
+    let enemy_color = piece.get_color().get_enemy_color();
+
+    for move_x in [-1, 1] {
+        let Some(target) = square.offset((move_x, y_direction)) else { continue };
+
+        let is_directly_attackable = Chess::is_color_on(board, target, enemy_color);
+        let can_en_passant = en_passant_target == Some(target);
+
+        if is_directly_attackable || can_en_passant {
+            moves[target] = true;
         }
     }
+}
 
+fn get_castling_moves(
+    piece: &Piece,
+    board: &Board<Option<Piece>>,
+    square: Square,
+    moves: &mut Board<bool>,
+) {
     // special case: castling
     if !piece.has_moved() {
         // note that this rank is not the same as real chess rank,
@@ -194,22 +396,24 @@ fn get_king_moves(
             0
         };
 
-        assert_eq!((x, y), (4, rank), "king cannot exist at {:?}", (x, y));
+        assert_eq!(square, Square::new(4, rank), "king cannot exist at {square}");
+
+        let (x, y) = (square.x(), square.y());
 
         // king side
-        moves[x + 2][y] = can_castle(board, (x, y), piece.get_color(), 1);
+        moves[x + 2][y] = can_castle(board, square, piece.get_color(), 1);
         // queen side
-        moves[x - 2][y] = can_castle(board, (x, y), piece.get_color(), -1);
+        moves[x - 2][y] = can_castle(board, square, piece.get_color(), -1);
     }
 }
 
 fn can_castle(
     board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
+    square: Square,
     color: PieceColor,
     x_dir: i32,
 ) -> bool {
-    let mut nx = x;
+    let (mut nx, y) = (square.x(), square.y());
 
     let is_castlable_rook = |cell: &Option<Piece>| -> bool {
         cell.is_some_and(|piece| {
@@ -226,68 +430,21 @@ fn can_castle(
             break;
         }
 
-        if !Chess::is_empty_on(board, (nx, y)) {
+        if !Chess::is_empty_on(board, Square::new(nx, y)) {
             return false;
         }
     }
 
-    return is_castlable_rook(&board[nx][y]);
-}
-
-fn get_moves_in_direction(
-    board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    (x_dir, y_dir): (i32, i32),
-    enemy_color: PieceColor,
-    moves: &mut Board<bool>,
-) {
-    let (mut nx, mut ny) = (x as i32, y as i32);
-
-    loop {
-        nx += x_dir;
-        ny += y_dir;
-
-        if !Chess::is_position_in_bound((nx, ny)) {
-            break;
-        }
-
-        let ind = (nx as usize, ny as usize);
-
-        let is_empty = Chess::is_empty_on(board, ind);
-        let is_enemy = Chess::is_color_on(board, ind, enemy_color);
-
-        if is_empty || is_enemy {
-            moves[ind.0][ind.1] = true;
-        }
-
-        if !is_empty {
-            break;
-        }
-    }
-}
-
-fn get_orthogonal_moves(
-    board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    enemy_color: PieceColor,
-    moves: &mut Board<bool>,
-) {
-    static DIRS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-
-    for (x_dir, y_dir) in DIRS {
-        get_moves_in_direction(board, (x, y), (x_dir, y_dir), enemy_color, moves);
-    }
+    is_castlable_rook(&board[nx][y])
 }
 
-fn get_diagonal_moves(
-    board: &Board<Option<Piece>>,
-    (x, y): (usize, usize),
-    enemy_color: PieceColor,
-    moves: &mut Board<bool>,
-) {
-    for x_dir in [-1, 1] {
-        for y_dir in [-1, 1] {
-            get_moves_in_direction(board, (x, y), (x_dir, y_dir), enemy_color, moves);
-        }
-    }
+// legal castling condition:
+// A player may not castle out of, through, or into check.
+// so the king's cell and the two cells towards x_dir must not be attacked by the enemy.
+// the cell next to the queen side rook only has to be empty, which can_castle takes care of.
+pub fn is_castling_safe(king: Square, x_dir: i32, enemy_attack_counts: &Board<u32>) -> bool {
+    (0..=2).all(|x| {
+        king.offset((x_dir * x, 0))
+            .is_some_and(|cell| enemy_attack_counts[cell] == 0)
+    })
 }
@@ -0,0 +1,265 @@
+use ggez::glam::Vec2;
+
+use crate::{
+    chess::Chess,
+    fen,
+    move_calculator::Move,
+    piece::{PieceColor, PieceType},
+    square::Square,
+};
+
+// the positions a game went through: the starting one, then one after each move
+pub struct Replay {
+    pub positions: Vec<Chess>,
+    // the number of the first move, which is not 1 for games starting from a FEN tag
+    first_move_number: usize,
+}
+
+impl Replay {
+    // replay the first game of a pgn, with moves in san or iccf numeric notation.
+    // comments, variations and annotations are skipped, and a FEN tag sets the starting position.
+    pub fn from_pgn(pgn: &str) -> Result<Self, String> {
+        let start = get_tag(pgn, "FEN").unwrap_or(fen::START_FEN);
+
+        let mut game = Chess::new(Vec2::ZERO)
+            .init_from_fen(start)
+            .ok_or_else(|| format!("the FEN tag \"{start}\" is not a legal position"))?;
+
+        let first_move_number = start
+            .split_whitespace()
+            .nth(5)
+            .and_then(|number| number.parse().ok())
+            .unwrap_or(1);
+
+        let mut positions = vec![game.clone()];
+
+        for san in get_moves(pgn) {
+            let parsed = parse_san(&game, san).or_else(|| parse_iccf(&game, san));
+
+            let (san_move, promote_to) = parsed.ok_or_else(|| {
+                format!("\"{san}\" is not a legal move after {}", game.get_fen())
+            })?;
+
+            let promote_to = promote_to.or(Some(PieceType::Queen));
+
+            game.apply_move(san_move.from, san_move.to, promote_to)
+                .map_err(|err| format!("\"{san}\" cannot be played: {err}"))?;
+
+            positions.push(game.clone());
+        }
+
+        Ok(Replay {
+            positions,
+            first_move_number,
+        })
+    }
+
+    // the position after a move given by its number, "12" for white's twelfth move
+    // and "12..." for black's
+    pub fn get_position_after(&self, move_number: &str) -> Option<&Chess> {
+        let (number, color) = match move_number.strip_suffix("...") {
+            Some(number) => (number, PieceColor::Black),
+            None => (move_number, PieceColor::White),
+        };
+
+        let number: usize = number.parse().ok()?;
+
+        let start_color = self.positions.first()?.get_turn_color();
+        let moves_before = 2 * number.checked_sub(self.first_move_number)?;
+
+        let ply = match color {
+            PieceColor::White => moves_before + 1,
+            PieceColor::Black => moves_before + 2,
+        };
+
+        // a game that starts with black to move has no white move of its first number,
+        // so every ply comes one earlier
+        let ply = match start_color {
+            PieceColor::White => ply,
+            PieceColor::Black => ply - 1,
+        };
+
+        // the starting position isn't after any move
+        if ply == 0 {
+            return None;
+        }
+
+        self.positions.get(ply)
+    }
+}
+
+// the value of a tag pair like [FEN "..."]
+fn get_tag<'a>(pgn: &'a str, name: &str) -> Option<&'a str> {
+    pgn.lines().find_map(|line| {
+        let value = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+        let (tag, value) = value.split_once(' ')?;
+
+        (tag == name).then(|| value.trim().trim_matches('"'))
+    })
+}
+
+// the san moves of the movetext, up to the result of the first game
+pub fn get_moves(pgn: &str) -> Vec<&str> {
+    let mut moves = Vec::new();
+
+    // how deep inside comments and variations the current token is.
+    // a stray closer is ignored instead of unbalancing the next opener
+    let mut comment_depth: u32 = 0;
+    let mut variation_depth: u32 = 0;
+
+    let mut rest = pgn;
+
+    while let Some(start) = rest.find(|curr: char| !curr.is_whitespace()) {
+        rest = &rest[start..];
+
+        let curr = rest.chars().next().expect("rest should start with a token");
+
+        let token_len = match curr {
+            '{' | '}' | '(' | ')' | ';' | '[' => 1,
+            _ => rest
+                .find(|curr: char| curr.is_whitespace() || "{}();[".contains(curr))
+                .unwrap_or(rest.len()),
+        };
+
+        let (token, next) = rest.split_at(token_len);
+        rest = next;
+
+        match token {
+            "{" => comment_depth += 1,
+            "}" => comment_depth = comment_depth.saturating_sub(1),
+            _ if comment_depth > 0 => {}
+            // the rest of the line is a comment or a tag pair
+            ";" | "[" => rest = rest.split_once('\n').map_or("", |(_, next)| next),
+            "(" => variation_depth += 1,
+            ")" => variation_depth = variation_depth.saturating_sub(1),
+            _ if variation_depth > 0 => {}
+            "1-0" | "0-1" | "1/2-1/2" | "*" => break,
+            _ if token.starts_with('$') => {}
+            _ => {
+                // move numbers may stick to the move, e.g. "12.Nf3", "12...Nf6" or "12.0-0",
+                // where castling is written with zeros.
+                // a bare number is a move number, unless it's as long as an iccf move like "5254"
+                let is_move_number =
+                    token.len() < 4 && token.bytes().all(|curr| curr.is_ascii_digit());
+
+                let san = match token.rsplit_once('.') {
+                    Some((_, san)) => san,
+                    None if is_move_number => "",
+                    None => token,
+                };
+
+                if !san.is_empty() {
+                    moves.push(san);
+                }
+            }
+        }
+    }
+
+    moves
+}
+
+// the legal move a san token describes, e.g. "Nbd7", "exd5", "e8=Q+" or "O-O"
+pub fn parse_san(game: &Chess, san: &str) -> Option<(Move, Option<PieceType>)> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+    let color = game.get_turn_color();
+
+    if matches!(san, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+        let dx = if san.len() == 3 { 2 } else { -2 };
+
+        let castling = game.get_legal_moves().iter().find(|legal_move| {
+            let is_king = game.get_board()[legal_move.from]
+                .is_some_and(|piece| piece.get_piece_type() == PieceType::King);
+
+            is_king && legal_move.to.offset((-dx, 0)) == Some(legal_move.from)
+        });
+
+        return castling.map(|castling| (*castling, None));
+    }
+
+    let (san, promote_to) = match san.char_indices().last()? {
+        (ind, curr @ ('Q' | 'R' | 'B' | 'N')) => (
+            san[..ind].trim_end_matches('='),
+            Some(get_piece_type(curr)?),
+        ),
+        _ => (san, None),
+    };
+
+    let (piece_type, san) = match san.chars().next()? {
+        curr @ ('K' | 'Q' | 'R' | 'B' | 'N') => (get_piece_type(curr)?, &san[1..]),
+        _ => (PieceType::Pawn, san),
+    };
+
+    let to = Square::from_algebraic(san.get(san.len().checked_sub(2)?..)?)?;
+
+    // whatever is left before the destination tells the origin apart, e.g. the "b" of "Nbd7"
+    let hint: Vec<char> = san[..san.len() - 2].chars().filter(|&curr| curr != 'x').collect();
+
+    let mut candidates = game.get_legal_moves().iter().filter(|legal_move| {
+        let Some(piece) = game.get_board()[legal_move.from] else { return false };
+
+        let name = legal_move.from.to_string();
+
+        piece.get_color() == color
+            && piece.get_piece_type() == piece_type
+            && legal_move.to == to
+            && hint.iter().all(|&curr| name.contains(curr))
+    });
+
+    match (candidates.next(), candidates.next()) {
+        (Some(san_move), None) => Some((*san_move, promote_to)),
+        _ => None,
+    }
+}
+
+// the legal move an iccf numeric token describes: the origin and destination cells,
+// then the piece a pawn promotes to, e.g. "5254", "5171" for white's short castling or "27281"
+pub fn parse_iccf(game: &Chess, iccf: &str) -> Option<(Move, Option<PieceType>)> {
+    let from = Square::from_iccf(iccf.get(0..2)?)?;
+    let to = Square::from_iccf(iccf.get(2..4)?)?;
+
+    let promote_to = match iccf.get(4..)? {
+        "" => None,
+        "1" => Some(PieceType::Queen),
+        "2" => Some(PieceType::Rook),
+        "3" => Some(PieceType::Bishop),
+        "4" => Some(PieceType::Knight),
+        _ => return None,
+    };
+
+    let iccf_move = game
+        .get_legal_moves()
+        .iter()
+        .find(|legal_move| legal_move.from == from && legal_move.to == to)?;
+
+    // only a pawn reaching the last rank takes a piece digit
+    if promote_to.is_some() && !iccf_move.is_promotion {
+        return None;
+    }
+
+    Some((*iccf_move, promote_to))
+}
+
+// the iccf numeric token of a move, the inverse of parse_iccf
+pub fn to_iccf(iccf_move: &Move, promote_to: Option<PieceType>) -> String {
+    let digit = match promote_to.filter(|_| iccf_move.is_promotion) {
+        Some(PieceType::Queen) => "1",
+        Some(PieceType::Rook) => "2",
+        Some(PieceType::Bishop) => "3",
+        Some(PieceType::Knight) => "4",
+        _ => "",
+    };
+
+    format!("{}{}{digit}", iccf_move.from.to_iccf(), iccf_move.to.to_iccf())
+}
+
+fn get_piece_type(letter: char) -> Option<PieceType> {
+    match letter {
+        'K' => Some(PieceType::King),
+        'Q' => Some(PieceType::Queen),
+        'R' => Some(PieceType::Rook),
+        'B' => Some(PieceType::Bishop),
+        'N' => Some(PieceType::Knight),
+        _ => None,
+    }
+}
@@ -1,10 +1,10 @@
 use std::fmt;
 
-use ggez::{graphics::Image, Context};
+use ggez::{glam::Vec2, graphics};
 
-use crate::game::Assets;
+use crate::game::{Assets, PieceSprite};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Piece {
     piece_type: PieceType,
     color: PieceColor,
@@ -20,20 +20,45 @@ impl Piece {
         }
     }
 
-    pub fn get_image<'a>(&self, ctx: &mut Context, assets: &'a mut Assets) -> &'a Image {
-        let sprite = self.color.to_string() + &self.piece_type.to_string();
+    // e.g. "wk" for the white king
+    pub fn get_sprite_name(&self) -> String {
+        self.color.to_string() + &self.piece_type.to_string()
+    }
 
-        assets.try_get_image(ctx, &sprite).unwrap()
+    // draw the piece centered on dest, with vector shapes if its sprite couldn't be loaded
+    pub fn draw(
+        &self,
+        canvas: &mut graphics::Canvas,
+        assets: &Assets,
+        dest: Vec2,
+        size: f32,
+        tint: graphics::Color,
+    ) {
+        match assets.get_piece_sprite(self) {
+            PieceSprite::Image(image) => {
+                let scale = size / image.width() as f32;
+
+                let drawparams = graphics::DrawParam::new()
+                    .dest(dest)
+                    .offset([0.5, 0.5]) // offset so that the sprite center and the drawing position align
+                    .scale([scale, scale])
+                    .color(tint);
+                canvas.draw(image, drawparams);
+            }
+            PieceSprite::Mesh(mesh) => {
+                let drawparams = graphics::DrawParam::new()
+                    .dest(dest)
+                    .scale([size, size])
+                    .color(tint);
+                canvas.draw(mesh, drawparams);
+            }
+        }
     }
 
     pub fn get_piece_type(&self) -> PieceType {
         self.piece_type
     }
 
-    pub fn get_piece_type_mut(&mut self) -> &mut PieceType {
-        &mut self.piece_type
-    }
-
     pub fn get_color(&self) -> PieceColor {
         self.color
     }
@@ -47,7 +72,7 @@ impl Piece {
     }
 
     pub fn promote(&mut self, promote_to: PieceType) {
-        if !matches!(self.get_piece_type(), PieceType::Pawn { .. }) {
+        if self.get_piece_type() != PieceType::Pawn {
             panic!("{self} cannot promote");
         }
 
@@ -77,7 +102,7 @@ impl fmt::Display for Piece {
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum PieceType {
-    Pawn { en_passant: bool },
+    Pawn,
     Rook,
     Bishop,
     Knight,
@@ -85,12 +110,36 @@ pub enum PieceType {
     Queen,
 }
 
+impl PieceType {
+    // conventional material value in pawns
+    pub fn get_value(&self) -> u32 {
+        match self {
+            PieceType::Pawn => 1,
+            PieceType::Knight | PieceType::Bishop => 3,
+            PieceType::Rook => 5,
+            PieceType::Queen => 9,
+            PieceType::King => 0,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PieceType::Pawn => "pawn",
+            PieceType::Knight => "knight",
+            PieceType::Bishop => "bishop",
+            PieceType::Rook => "rook",
+            PieceType::Queen => "queen",
+            PieceType::King => "king",
+        }
+    }
+}
+
 impl fmt::Display for PieceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use PieceType::*;
 
         let c = match self {
-            Pawn { .. } => 'p',
+            Pawn => 'p',
             Rook => 'r',
             Bishop => 'b',
             Knight => 'n',
@@ -115,6 +164,13 @@ impl PieceColor {
             PieceColor::Black => PieceColor::White,
         }
     }
+
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            PieceColor::White => "White",
+            PieceColor::Black => "Black",
+        }
+    }
 }
 
 impl fmt::Display for PieceColor {
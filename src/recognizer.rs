@@ -0,0 +1,92 @@
+use std::{
+    env,
+    ffi::OsString,
+    fmt, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use crate::fen;
+
+// the external program that reads a position off a screenshot
+pub const RECOGNIZER_VAR: &str = "RUST_CHESS_RECOGNIZER";
+
+// hook for an external FEN recognition tool.
+// it's run as `<command> <screenshot>` and prints the FEN of the pictured position on stdout.
+pub struct Recognizer {
+    command: OsString,
+}
+
+pub enum RecognizeError {
+    // the program couldn't be started at all
+    Spawn(io::Error),
+    Failed { status: ExitStatus, stderr: String },
+    InvalidFen(String),
+}
+
+impl Recognizer {
+    // the recognizer set in the environment, None if there's none
+    pub fn from_env() -> Option<Self> {
+        env::var_os(RECOGNIZER_VAR)
+            .filter(|command| !command.is_empty())
+            .map(|command| Recognizer { command })
+    }
+
+    // run the recognizer on its own thread, since reading an image can take a while
+    pub fn spawn(self, screenshot: PathBuf) -> Receiver<Result<String, RecognizeError>> {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            // nobody is waiting anymore if the receiver is gone
+            let _ = sender.send(self.recognize(&screenshot));
+        });
+
+        receiver
+    }
+
+    pub fn recognize(&self, screenshot: &Path) -> Result<String, RecognizeError> {
+        let output = Command::new(&self.command)
+            .arg(screenshot)
+            .output()
+            .map_err(RecognizeError::Spawn)?;
+
+        if !output.status.success() {
+            return Err(RecognizeError::Failed {
+                status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().next().unwrap_or_default().trim();
+
+        // a picture only shows the pieces, so a bare placement gets white to move and no castling
+        let fen = if line.contains(' ') {
+            line.to_string()
+        } else {
+            format!("{line} w - - 0 1")
+        };
+
+        match fen::parse_fen(&fen) {
+            Some(..) => Ok(fen),
+            None => Err(RecognizeError::InvalidFen(line.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for RecognizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RecognizeError::*;
+
+        match self {
+            Spawn(err) => write!(f, "cannot run the recognizer: {err}"),
+            Failed { status, stderr } if stderr.is_empty() => {
+                write!(f, "the recognizer failed with {status}")
+            }
+            Failed { status, stderr } => write!(f, "the recognizer failed with {status}: {stderr}"),
+            InvalidFen(output) => write!(f, "the recognizer gave no legal position: \"{output}\""),
+        }
+    }
+}
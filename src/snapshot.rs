@@ -0,0 +1,61 @@
+use crate::{
+    chess::Board,
+    fen::{self, CASTLING_RIGHTS},
+    piece::*,
+    square::Square,
+};
+
+// a read-only copy of a position, for readers on other threads (or anywhere else)
+// that need a consistent view without holding on to the game itself.
+// it's plain data, so it's cheap to copy and can be sent and shared freely.
+#[derive(Copy, Clone, PartialEq)]
+pub struct PositionSnapshot {
+    board: Board<Option<Piece>>,
+    turn_color: PieceColor,
+    // in the order of fen::CASTLING_RIGHTS
+    castling_rights: [bool; 4],
+    en_passant_target: Option<Square>,
+}
+
+impl PositionSnapshot {
+    pub fn new(
+        board: &Board<Option<Piece>>,
+        turn_color: PieceColor,
+        en_passant_target: Option<Square>,
+    ) -> Self {
+        PositionSnapshot {
+            board: *board,
+            turn_color,
+            castling_rights: CASTLING_RIGHTS.map(|right| fen::has_castling_right(board, right)),
+            en_passant_target,
+        }
+    }
+
+    pub fn get_board(&self) -> &Board<Option<Piece>> {
+        &self.board
+    }
+
+    pub fn get_turn_color(&self) -> PieceColor {
+        self.turn_color
+    }
+
+    // whether the color may still castle to the given side, ignoring checks and blockers
+    pub fn can_castle(&self, color: PieceColor, is_king_side: bool) -> bool {
+        let ind = match (color, is_king_side) {
+            (PieceColor::White, true) => 0,
+            (PieceColor::White, false) => 1,
+            (PieceColor::Black, true) => 2,
+            (PieceColor::Black, false) => 3,
+        };
+
+        self.castling_rights[ind]
+    }
+
+    pub fn get_en_passant_target(&self) -> Option<Square> {
+        self.en_passant_target
+    }
+
+    pub fn get_fen(&self) -> String {
+        fen::to_fen(&self.board, self.turn_color, self.en_passant_target)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::chess::{BOARD_HEIGHT, BOARD_WIDTH};
+
+// a cell of the board, stored as its index in 0..64 counted from a8 to h1.
+// x and y are the column from the left and the row from the top, as drawn on the screen.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Square(u8);
+
+impl Square {
+    pub fn new(x: usize, y: usize) -> Self {
+        assert!(
+            x < BOARD_WIDTH && y < BOARD_HEIGHT,
+            "({x}, {y}) is out of the board"
+        );
+
+        Square((y * BOARD_WIDTH + x) as u8)
+    }
+
+    // None if (x, y) is out of the board
+    pub fn try_new(x: i32, y: i32) -> Option<Self> {
+        let is_in_bound =
+            (0..BOARD_WIDTH as i32).contains(&x) && (0..BOARD_HEIGHT as i32).contains(&y);
+
+        is_in_bound.then(|| Square::new(x as usize, y as usize))
+    }
+
+    pub fn from_index(ind: usize) -> Self {
+        assert!(
+            ind < BOARD_WIDTH * BOARD_HEIGHT,
+            "{ind} is out of the board"
+        );
+
+        Square(ind as u8)
+    }
+
+    // e.g. "e2" -> (4, 6)
+    pub fn from_algebraic(name: &str) -> Option<Self> {
+        let &[file, rank] = name.as_bytes() else {
+            return None;
+        };
+
+        let x = file.checked_sub(b'a')? as usize;
+        let rank = (rank as char).to_digit(10)? as usize;
+
+        if x >= BOARD_WIDTH || !(1..=BOARD_HEIGHT).contains(&rank) {
+            return None;
+        }
+
+        Some(Square::new(x, BOARD_HEIGHT - rank))
+    }
+
+    // the iccf numeric name, the file then the rank, both counted from 1, e.g. "52" -> e2
+    pub fn from_iccf(name: &str) -> Option<Self> {
+        let &[file, rank] = name.as_bytes() else {
+            return None;
+        };
+
+        let file = (file as char).to_digit(10)? as usize;
+        let rank = (rank as char).to_digit(10)? as usize;
+
+        if !(1..=BOARD_WIDTH).contains(&file) || !(1..=BOARD_HEIGHT).contains(&rank) {
+            return None;
+        }
+
+        Some(Square::new(file - 1, BOARD_HEIGHT - rank))
+    }
+
+    // e.g. e2 -> "52"
+    pub fn to_iccf(self) -> String {
+        format!("{}{}", self.file() + 1, self.rank() + 1)
+    }
+
+    // every square, row by row from the top
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..BOARD_WIDTH * BOARD_HEIGHT).map(Square::from_index)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn x(self) -> usize {
+        self.index() % BOARD_WIDTH
+    }
+
+    pub fn y(self) -> usize {
+        self.index() / BOARD_WIDTH
+    }
+
+    // 0 for the a-file
+    pub fn file(self) -> usize {
+        self.x()
+    }
+
+    // 0 for the first rank, white's back rank
+    pub fn rank(self) -> usize {
+        BOARD_HEIGHT - 1 - self.y()
+    }
+
+    // the square moved by (dx, dy), None if it falls off the board
+    pub fn offset(self, (dx, dy): (i32, i32)) -> Option<Self> {
+        Square::try_new(self.x() as i32 + dx, self.y() as i32 + dy)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            (b'a' + self.file() as u8) as char,
+            self.rank() + 1
+        )
+    }
+}
+
+// lets boards be indexed with board[square] as well as board[x][y]
+impl<T> Index<Square> for [[T; BOARD_HEIGHT]; BOARD_WIDTH] {
+    type Output = T;
+
+    fn index(&self, square: Square) -> &T {
+        &self[square.x()][square.y()]
+    }
+}
+
+impl<T> IndexMut<Square> for [[T; BOARD_HEIGHT]; BOARD_WIDTH] {
+    fn index_mut(&mut self, square: Square) -> &mut T {
+        &mut self[square.x()][square.y()]
+    }
+}
@@ -0,0 +1,83 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{self, TextAlign, TextLayout},
+};
+
+// a line of text on the HUD.
+// laying out text is not free, so it's only rebuilt when the string changes.
+pub struct Label {
+    scale: f32,
+    layout: TextLayout,
+
+    content: String,
+    text: graphics::Text,
+}
+
+impl Label {
+    pub fn new(scale: f32, layout: TextLayout) -> Self {
+        Label {
+            scale,
+            layout,
+            content: String::new(),
+            text: Label::build_text("", scale, layout),
+        }
+    }
+
+    pub fn draw(&mut self, canvas: &mut graphics::Canvas, content: &str, dest: Vec2) {
+        if content != self.content {
+            self.content = content.to_string();
+            self.text = Label::build_text(content, self.scale, self.layout);
+        }
+
+        canvas.draw(
+            &self.text,
+            graphics::DrawParam::from(dest).color(graphics::Color::BLACK),
+        );
+    }
+
+    fn build_text(content: &str, scale: f32, layout: TextLayout) -> graphics::Text {
+        graphics::Text::new(content)
+            .set_scale(scale)
+            .set_layout(layout)
+            .clone()
+    }
+}
+
+// the labels around the board, kept from frame to frame
+pub struct Hud {
+    pub turn: Label,
+    pub state: Label,
+    pub modes: Label,
+    pub moves: Label,
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Hud {
+            turn: Label::new(32., TextLayout::top_left()),
+            state: Label::new(
+                32.,
+                TextLayout {
+                    // right align
+                    h_align: TextAlign::End,
+                    v_align: TextAlign::Begin,
+                },
+            ),
+            modes: Label::new(
+                24.,
+                TextLayout {
+                    h_align: TextAlign::Begin,
+                    v_align: TextAlign::End,
+                },
+            ),
+            // in the narrow margin right of the board
+            moves: Label::new(
+                16.,
+                TextLayout {
+                    h_align: TextAlign::End,
+                    v_align: TextAlign::Begin,
+                },
+            ),
+        }
+    }
+}
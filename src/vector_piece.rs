@@ -0,0 +1,215 @@
+use ggez::{
+    graphics::{self, DrawMode, Mesh, MeshBuilder},
+    Context, GameResult,
+};
+
+use crate::piece::*;
+
+// pieces drawn from simple shapes, used when the sprites can't be loaded and in diagrams.
+// coordinates are in a unit cell centered on (0, 0) with y pointing down,
+// so the mesh is scaled to the cell size when it's drawn.
+
+enum Shape {
+    Polygon(&'static [(f32, f32)]),
+    Circle((f32, f32), f32),
+    Ellipse((f32, f32), f32, f32),
+    // small detail in a color contrasting the piece, e.g. the knight's eye
+    Dot((f32, f32), f32),
+}
+
+const OUTLINE_WIDTH: f32 = 0.025;
+const WHITE_FILL: u32 = 0xF8F8F8;
+const BLACK_FILL: u32 = 0x262626;
+const OUTLINE: u32 = 0x000000;
+const TOLERANCE: f32 = 0.002;
+
+const BASE: Shape = Shape::Polygon(&[(-0.3, 0.3), (0.3, 0.3), (0.3, 0.4), (-0.3, 0.4)]);
+
+const PAWN: &[Shape] = &[
+    Shape::Polygon(&[(-0.1, -0.04), (0.1, -0.04), (0.18, 0.3), (-0.18, 0.3)]),
+    Shape::Circle((0.0, -0.15), 0.12),
+    BASE,
+];
+
+const ROOK: &[Shape] = &[
+    Shape::Polygon(&[(-0.17, -0.15), (0.17, -0.15), (0.2, 0.3), (-0.2, 0.3)]),
+    Shape::Polygon(&[
+        (-0.24, -0.34),
+        (-0.14, -0.34),
+        (-0.14, -0.26),
+        (-0.05, -0.26),
+        (-0.05, -0.34),
+        (0.05, -0.34),
+        (0.05, -0.26),
+        (0.14, -0.26),
+        (0.14, -0.34),
+        (0.24, -0.34),
+        (0.24, -0.15),
+        (-0.24, -0.15),
+    ]),
+    BASE,
+];
+
+const KNIGHT: &[Shape] = &[
+    Shape::Polygon(&[
+        (-0.2, 0.3),
+        (-0.12, 0.02),
+        (-0.26, -0.02),
+        (-0.28, -0.12),
+        (-0.08, -0.3),
+        (0.0, -0.38),
+        (0.06, -0.3),
+        (0.2, -0.18),
+        (0.24, 0.3),
+    ]),
+    Shape::Dot((-0.05, -0.2), 0.025),
+    BASE,
+];
+
+const BISHOP: &[Shape] = &[
+    Shape::Polygon(&[(-0.08, 0.1), (0.08, 0.1), (0.2, 0.3), (-0.2, 0.3)]),
+    Shape::Ellipse((0.0, -0.08), 0.14, 0.2),
+    Shape::Circle((0.0, -0.33), 0.05),
+    BASE,
+];
+
+const QUEEN: &[Shape] = &[
+    Shape::Polygon(&[
+        (-0.24, 0.3),
+        (-0.3, -0.2),
+        (-0.14, 0.0),
+        (-0.08, -0.28),
+        (0.0, -0.02),
+        (0.08, -0.28),
+        (0.14, 0.0),
+        (0.3, -0.2),
+        (0.24, 0.3),
+    ]),
+    Shape::Circle((-0.3, -0.22), 0.045),
+    Shape::Circle((-0.08, -0.3), 0.045),
+    Shape::Circle((0.08, -0.3), 0.045),
+    Shape::Circle((0.3, -0.22), 0.045),
+    BASE,
+];
+
+const KING: &[Shape] = &[
+    Shape::Polygon(&[
+        (-0.24, 0.3),
+        (-0.28, -0.08),
+        (-0.1, -0.02),
+        (0.0, -0.14),
+        (0.1, -0.02),
+        (0.28, -0.08),
+        (0.24, 0.3),
+    ]),
+    Shape::Polygon(&[
+        (-0.04, -0.42),
+        (0.04, -0.42),
+        (0.04, -0.34),
+        (0.1, -0.34),
+        (0.1, -0.27),
+        (0.04, -0.27),
+        (0.04, -0.14),
+        (-0.04, -0.14),
+        (-0.04, -0.27),
+        (-0.1, -0.27),
+        (-0.1, -0.34),
+        (-0.04, -0.34),
+    ]),
+    BASE,
+];
+
+fn get_shapes(piece_type: PieceType) -> &'static [Shape] {
+    match piece_type {
+        PieceType::Pawn => PAWN,
+        PieceType::Rook => ROOK,
+        PieceType::Knight => KNIGHT,
+        PieceType::Bishop => BISHOP,
+        PieceType::Queen => QUEEN,
+        PieceType::King => KING,
+    }
+}
+
+// fill and detail colors of the piece
+fn get_colors(piece: &Piece) -> (u32, u32) {
+    match piece.get_color() {
+        PieceColor::White => (WHITE_FILL, 0x000000),
+        PieceColor::Black => (BLACK_FILL, 0xFFFFFF),
+    }
+}
+
+pub fn build_piece_mesh(ctx: &Context, piece: &Piece) -> GameResult<Mesh> {
+    let (fill, detail) = get_colors(piece);
+
+    let fill = graphics::Color::from_rgb_u32(fill);
+    let detail = graphics::Color::from_rgb_u32(detail);
+    let outline = graphics::Color::from_rgb_u32(OUTLINE);
+
+    let mut builder = MeshBuilder::new();
+
+    for shape in get_shapes(piece.get_piece_type()) {
+        if let Shape::Dot(center, radius) = *shape {
+            builder.circle(DrawMode::fill(), point_of(center), radius, TOLERANCE, detail)?;
+            continue;
+        }
+
+        for (mode, color) in [
+            (DrawMode::fill(), fill),
+            (DrawMode::stroke(OUTLINE_WIDTH), outline),
+        ] {
+            match *shape {
+                Shape::Polygon(points) => {
+                    builder.polygon(mode, points_of(points).as_slice(), color)?;
+                }
+                Shape::Circle(center, radius) => {
+                    builder.circle(mode, point_of(center), radius, TOLERANCE, color)?;
+                }
+                Shape::Ellipse(center, rx, ry) => {
+                    builder.ellipse(mode, point_of(center), rx, ry, TOLERANCE, color)?;
+                }
+                Shape::Dot(..) => unreachable!("dots are drawn without an outline"),
+            }
+        }
+    }
+
+    Ok(Mesh::from_data(ctx, builder.build()))
+}
+
+// the same shapes as svg elements, scaled to `size` and centered on `center`
+pub fn build_piece_svg(piece: &Piece, (x, y): (f32, f32), size: f32) -> String {
+    let (fill, detail) = get_colors(piece);
+
+    let mut svg = format!(
+        "<g transform=\"translate({x} {y}) scale({size})\" fill=\"#{fill:06X}\" \
+         stroke=\"#{OUTLINE:06X}\" stroke-width=\"{OUTLINE_WIDTH}\" stroke-linejoin=\"round\">"
+    );
+
+    for shape in get_shapes(piece.get_piece_type()) {
+        let element = match *shape {
+            Shape::Polygon(points) => {
+                let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+
+                format!("<polygon points=\"{}\"/>", points.join(" "))
+            }
+            Shape::Circle((cx, cy), r) => format!("<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{r}\"/>"),
+            Shape::Ellipse((cx, cy), rx, ry) => {
+                format!("<ellipse cx=\"{cx}\" cy=\"{cy}\" rx=\"{rx}\" ry=\"{ry}\"/>")
+            }
+            Shape::Dot((cx, cy), r) => format!(
+                "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{r}\" fill=\"#{detail:06X}\" stroke=\"none\"/>"
+            ),
+        };
+
+        svg += &element;
+    }
+
+    svg + "</g>"
+}
+
+fn point_of((x, y): (f32, f32)) -> [f32; 2] {
+    [x, y]
+}
+
+fn points_of(points: &[(f32, f32)]) -> Vec<[f32; 2]> {
+    points.iter().copied().map(point_of).collect()
+}
@@ -0,0 +1,40 @@
+// moves played without input are refused with a reason instead of corrupting the game
+
+use chess::{chess::MoveError, Chess, Move, Square};
+use ggez::glam::vec2;
+
+fn square(name: &str) -> Square {
+    Square::from_algebraic(name).expect("the test should name a real cell")
+}
+
+#[test]
+fn pending_promotion_blocks_further_moves() {
+    let mut chess = Chess::new(vec2(0., 0.))
+        .init_from_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1")
+        .expect("the position should be legal");
+
+    // without a piece to promote to, the pawn waits for the player's choice
+    assert_eq!(chess.apply_move(square("e7"), square("e8"), None), Ok(()));
+
+    assert_eq!(
+        chess.apply_move(square("h1"), square("g1"), None),
+        Err(MoveError::PendingPromotion)
+    );
+    assert!(chess.get_legal_moves().is_empty());
+    assert_eq!(chess.get_fen(), "4P3/8/8/8/8/8/8/k6K w - - 0 1");
+}
+
+#[test]
+fn simulating_a_foreign_move_is_refused() {
+    let chess = Chess::new(vec2(0., 0.)).init();
+
+    let foreign = Move::new(chess.get_board(), square("e7"), square("e5"));
+
+    assert_eq!(
+        chess.simulate_move(&foreign).err(),
+        Some(MoveError::Illegal {
+            from: square("e7"),
+            to: square("e5"),
+        })
+    );
+}
@@ -0,0 +1,122 @@
+// en passant regressions: the target only lasts for the turn right after a double step,
+// survives FEN round trips, and the capture is checked like any other move
+
+use chess::{Chess, Square};
+use ggez::glam::vec2;
+
+fn from_fen(fen: &str) -> Chess {
+    Chess::new(vec2(0., 0.))
+        .init_from_fen(fen)
+        .unwrap_or_else(|| panic!("{fen} should be a legal position"))
+}
+
+fn square(name: &str) -> Square {
+    Square::from_algebraic(name).expect("the test should name a real cell")
+}
+
+fn is_legal(chess: &Chess, from: &str, to: &str) -> bool {
+    chess
+        .get_legal_moves()
+        .iter()
+        .any(|legal_move| legal_move.from == square(from) && legal_move.to == square(to))
+}
+
+fn play(chess: &mut Chess, moves: &[(&str, &str)]) {
+    for &(from, to) in moves {
+        assert!(
+            chess.apply_move(square(from), square(to), None).is_ok(),
+            "{from} to {to} should be legal in {}",
+            chess.get_fen()
+        );
+    }
+}
+
+#[test]
+fn target_is_set_by_a_double_step_only() {
+    let mut chess = Chess::new(vec2(0., 0.)).init();
+
+    play(&mut chess, &[("e2", "e4")]);
+    assert_eq!(chess.get_snapshot().get_en_passant_target(), Some(square("e3")));
+
+    play(&mut chess, &[("g8", "f6")]);
+    assert_eq!(chess.get_snapshot().get_en_passant_target(), None);
+
+    play(&mut chess, &[("e4", "e5"), ("d7", "d6")]);
+    assert_eq!(chess.get_snapshot().get_en_passant_target(), None);
+}
+
+#[test]
+fn capture_is_only_allowed_right_away() {
+    let mut chess = Chess::new(vec2(0., 0.)).init();
+
+    play(&mut chess, &[("e2", "e4"), ("a7", "a6"), ("e4", "e5"), ("d7", "d5")]);
+    assert!(is_legal(&chess, "e5", "d6"));
+
+    play(&mut chess, &[("g1", "f3"), ("b8", "c6")]);
+    assert!(!is_legal(&chess, "e5", "d6"));
+}
+
+#[test]
+fn capture_removes_the_passed_pawn() {
+    let mut chess = from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 1");
+
+    // only the pawn that just moved can be taken, not the one that moved a turn before
+    assert!(is_legal(&chess, "e5", "d6"));
+    assert!(!is_legal(&chess, "e5", "f6"));
+
+    play(&mut chess, &[("e5", "d6")]);
+
+    assert!(chess.get_board()[square("d5")].is_none());
+    assert_eq!(
+        chess.get_fen(),
+        "rnbqkbnr/ppp1p1pp/3P4/5p2/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+}
+
+#[test]
+fn capture_cannot_uncover_a_check_along_the_rank() {
+    // both pawns leave the fifth rank, opening it for the rook
+    let chess = from_fen("8/8/8/KPp4r/8/8/8/7k w - c6 0 1");
+
+    assert!(!is_legal(&chess, "b5", "c6"));
+    assert!(is_legal(&chess, "b5", "b6"));
+}
+
+#[test]
+fn capture_cannot_uncover_a_check_along_the_diagonal() {
+    // the captured pawn is the one shielding the king from the bishop
+    let chess = from_fen("7k/1b6/8/3pP3/8/5K2/8/8 w - d6 0 1");
+
+    assert!(!is_legal(&chess, "e5", "d6"));
+    assert!(is_legal(&chess, "e5", "e6"));
+}
+
+#[test]
+fn capture_can_take_a_checking_pawn() {
+    // the pawn that moved two cells gives check and can only be taken en passant
+    let chess = from_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1");
+
+    assert!(is_legal(&chess, "e4", "d3"));
+    assert!(!is_legal(&chess, "e4", "e3"));
+}
+
+#[test]
+fn target_survives_a_fen_round_trip() {
+    let fen = "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 1";
+
+    assert_eq!(from_fen(fen).get_fen(), fen);
+}
+
+#[test]
+fn target_without_a_double_step_is_rejected() {
+    let new_game = |fen| Chess::new(vec2(0., 0.)).init_from_fen(fen);
+
+    // nothing stands past the target
+    assert!(new_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 1").is_none());
+    // the pawn past the target belongs to the side to move
+    assert!(new_game("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e3 0 1").is_none());
+    // the pawn couldn't have come through an occupied cell
+    assert!(new_game("rnbqkbnr/pppp1ppp/4p3/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 1").is_none());
+    // a pawn can't have passed a cell off the third or sixth rank
+    assert!(new_game("4k3/8/8/8/8/4pP2/8/4K3 w - e4 0 1").is_none());
+}
@@ -0,0 +1,118 @@
+// iccf numeric notation, as correspondence players send their moves
+
+use chess::{pgn, piece::PieceType, Chess, Square};
+use ggez::glam::vec2;
+
+fn from_fen(fen: &str) -> Chess {
+    Chess::new(vec2(0., 0.))
+        .init_from_fen(fen)
+        .unwrap_or_else(|| panic!("{fen} should be a legal position"))
+}
+
+fn square(name: &str) -> Square {
+    Square::from_algebraic(name).expect("the test should name a real cell")
+}
+
+// the move an iccf token describes written as origin, destination and promotion, e.g. "e7e8n"
+fn parse(chess: &Chess, iccf: &str) -> Option<String> {
+    let (iccf_move, promote_to) = pgn::parse_iccf(chess, iccf)?;
+    let promote_to = promote_to.map_or(String::new(), |piece_type| piece_type.to_string());
+
+    Some(format!("{}{}{promote_to}", iccf_move.from, iccf_move.to))
+}
+
+#[test]
+fn squares_are_numbered_by_file_then_rank() {
+    assert_eq!(Square::from_iccf("11"), Some(square("a1")));
+    assert_eq!(Square::from_iccf("52"), Some(square("e2")));
+    assert_eq!(Square::from_iccf("88"), Some(square("h8")));
+
+    assert_eq!(square("d7").to_iccf(), "47");
+
+    for name in ["01", "19", "90", "5", "523", "e2"] {
+        assert_eq!(Square::from_iccf(name), None, "{name} isn't a cell");
+    }
+
+    assert!(Square::all().all(|cell| Square::from_iccf(&cell.to_iccf()) == Some(cell)));
+}
+
+#[test]
+fn moves_are_read_from_the_legal_ones() {
+    let chess = Chess::new(vec2(0., 0.)).init();
+
+    assert_eq!(parse(&chess, "5254"), Some("e2e4".to_string()));
+    assert_eq!(parse(&chess, "7163"), Some("g1f3".to_string()));
+    // a black move, and a white one that can't be played
+    assert_eq!(parse(&chess, "5755"), None);
+    assert_eq!(parse(&chess, "4185"), None);
+    // no piece digit without a promotion
+    assert_eq!(parse(&chess, "52541"), None);
+
+    let castling = from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1");
+    assert_eq!(parse(&castling, "5878"), Some("e8g8".to_string()));
+    assert_eq!(parse(&castling, "5838"), Some("e8c8".to_string()));
+}
+
+#[test]
+fn promotions_end_with_the_piece_digit() {
+    let chess = from_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1");
+
+    assert_eq!(parse(&chess, "57581"), Some("e7e8q".to_string()));
+    assert_eq!(parse(&chess, "57582"), Some("e7e8r".to_string()));
+    assert_eq!(parse(&chess, "57583"), Some("e7e8b".to_string()));
+    assert_eq!(parse(&chess, "57584"), Some("e7e8n".to_string()));
+    assert_eq!(parse(&chess, "57585"), None);
+}
+
+#[test]
+fn moves_survive_a_round_trip() {
+    let chess = from_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1");
+
+    for legal_move in chess.get_legal_moves() {
+        let iccf = pgn::to_iccf(legal_move, None);
+
+        assert_eq!(
+            parse(&chess, &iccf),
+            Some(format!("{}{}", legal_move.from, legal_move.to))
+        );
+    }
+
+    let (promotion, _) = pgn::parse_iccf(&chess, "5758").expect("e7e8 should be legal");
+
+    assert_eq!(pgn::to_iccf(&promotion, Some(PieceType::Knight)), "57584");
+    // a move that isn't a promotion has no piece to name
+    let (king_move, _) = pgn::parse_iccf(&chess, "8171").expect("h1g1 should be legal");
+    assert_eq!(pgn::to_iccf(&king_move, Some(PieceType::Queen)), "8171");
+}
+
+#[test]
+fn games_can_be_replayed_from_iccf_moves() {
+    // the scholar's mate, mixing both notations
+    let replay = pgn::Replay::from_pgn("1. 5254 5755 2. Bc4 2...2836 3. 4185 7866 4. 8567 *")
+        .expect("the game should replay");
+
+    let game = replay.positions.last().expect("there should be a final position");
+
+    assert!(game.is_checkmate());
+    assert_eq!(
+        game.get_fen(),
+        "r1bqkb1r/pppp1Qpp/2n2n2/4p3/2B1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1"
+    );
+}
+
+#[test]
+fn played_moves_are_kept_for_the_move_list() {
+    let mut chess = from_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1");
+
+    chess
+        .apply_move(square("e7"), square("e8"), Some(PieceType::Knight))
+        .expect("the promotion should be legal");
+
+    let played: Vec<String> = chess
+        .get_played_moves()
+        .iter()
+        .map(|(played_move, promote_to)| pgn::to_iccf(played_move, *promote_to))
+        .collect();
+
+    assert_eq!(played, ["57584"]);
+}
@@ -0,0 +1,61 @@
+// the mate search against positions with a known forced mate
+
+use std::sync::atomic::AtomicBool;
+
+use chess::{mate_search, Chess, Move};
+use ggez::glam::vec2;
+
+fn from_fen(fen: &str) -> Chess {
+    Chess::new(vec2(0., 0.))
+        .init_from_fen(fen)
+        .unwrap_or_else(|| panic!("{fen} should be a legal position"))
+}
+
+fn find_mate(chess: &Chess, moves: u32, checks_only: bool) -> Option<Vec<Move>> {
+    mate_search::find_mate(chess, moves, checks_only, &AtomicBool::new(false))
+}
+
+fn to_names(line: &[Move]) -> Vec<String> {
+    line.iter()
+        .map(|mate_move| format!("{}{}", mate_move.from, mate_move.to))
+        .collect()
+}
+
+// Morphy's 1. Qd8+ Bxd8 2. Re8#
+const MATE_IN_TWO: &str = "r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 0 1";
+
+#[test]
+fn finds_a_mate_in_two() {
+    let line = find_mate(&from_fen(MATE_IN_TWO), 3, false);
+
+    assert_eq!(
+        line.as_deref().map(to_names),
+        Some(vec!["d5d8".to_string(), "e7d8".to_string(), "e1e8".to_string()])
+    );
+}
+
+#[test]
+fn finds_a_mate_in_two_with_checks_only() {
+    let line = find_mate(&from_fen(MATE_IN_TWO), 2, true);
+
+    assert_eq!(line.map(|line| line.len()), Some(3));
+}
+
+#[test]
+fn finds_no_mate_beyond_the_limit() {
+    assert!(find_mate(&from_fen(MATE_IN_TWO), 1, false).is_none());
+}
+
+#[test]
+fn finds_a_back_rank_mate_in_one() {
+    let line = find_mate(&from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"), 1, false);
+
+    assert_eq!(line.as_deref().map(to_names), Some(vec!["a1a8".to_string()]));
+}
+
+#[test]
+fn aborted_search_finds_nothing() {
+    let chess = from_fen(MATE_IN_TWO);
+
+    assert!(mate_search::find_mate(&chess, 3, false, &AtomicBool::new(true)).is_none());
+}
@@ -0,0 +1,20 @@
+// the middlegames the R key starts from must all be legal positions
+
+use chess::{middlegame::MIDDLEGAMES, Chess};
+use ggez::glam::vec2;
+
+#[test]
+fn middlegames_survive_a_fen_round_trip() {
+    // the move counters aren't kept, so only the fields before them are compared
+    let fields = |fen: &str| fen.split(' ').take(4).collect::<Vec<_>>().join(" ");
+
+    for &middlegame in MIDDLEGAMES {
+        let mut chess = Chess::new(vec2(0., 0.)).init();
+
+        assert!(
+            chess.restart_from_fen(middlegame),
+            "{middlegame} should be a legal position"
+        );
+        assert_eq!(fields(&chess.get_fen()), fields(middlegame));
+    }
+}
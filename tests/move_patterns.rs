@@ -0,0 +1,88 @@
+// move patterns on hand-built boards, for the fairy pieces no standard piece type uses
+
+use chess::{
+    move_calculator::{self, MovePattern},
+    piece::{Piece, PieceColor, PieceType},
+    Board, Square,
+};
+
+const QUEEN_DIRS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+fn square(name: &str) -> Square {
+    Square::from_algebraic(name).expect("the test should name a real cell")
+}
+
+fn place(board: &mut Board<Option<Piece>>, pieces: &[(&str, PieceColor)]) {
+    for &(name, color) in pieces {
+        board[square(name)] = Some(Piece::new(PieceType::Pawn, color));
+    }
+}
+
+// the cells a white grasshopper on d4 reaches, by name
+fn get_grasshopper_cells(board: &Board<Option<Piece>>, is_control: bool) -> Vec<String> {
+    let mut cells = [[false; 8]; 8];
+    let grasshopper = MovePattern::Hopper(&QUEEN_DIRS);
+
+    move_calculator::get_pattern_cells(
+        board,
+        square("d4"),
+        &grasshopper,
+        PieceColor::White,
+        is_control,
+        &mut cells,
+    );
+
+    Square::all()
+        .filter(|&cell| cells[cell])
+        .map(|cell| cell.to_string())
+        .collect()
+}
+
+#[test]
+fn hopper_lands_right_behind_the_first_piece() {
+    let mut board = [[None; 8]; 8];
+
+    place(
+        &mut board,
+        &[
+            // a hurdle of either color can be jumped, landing on an empty cell
+            ("d6", PieceColor::White),
+            ("e5", PieceColor::Black),
+            // it slides up to a far hurdle
+            ("b6", PieceColor::White),
+            ("e3", PieceColor::Black),
+            // landing on an enemy captures it
+            ("f4", PieceColor::White),
+            ("g4", PieceColor::Black),
+            // but not on an own piece
+            ("b4", PieceColor::Black),
+            ("a4", PieceColor::White),
+            // and not off the board behind a hurdle on the edge
+            ("a1", PieceColor::Black),
+        ],
+    );
+
+    assert_eq!(get_grasshopper_cells(&board, false), ["a7", "d7", "f6", "g4", "f2"]);
+
+    // the own piece behind a hurdle is still protected
+    assert_eq!(
+        get_grasshopper_cells(&board, true),
+        ["a7", "d7", "f6", "a4", "g4", "f2"]
+    );
+}
+
+#[test]
+fn hopper_without_a_hurdle_goes_nowhere() {
+    let board = [[None; 8]; 8];
+
+    assert!(get_grasshopper_cells(&board, false).is_empty());
+}
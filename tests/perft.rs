@@ -0,0 +1,57 @@
+// move generation counted against the known perft numbers of well-studied positions.
+// none of these depths reach a promotion, so simulating every promotion as a queen is enough
+
+use chess::Chess;
+use ggez::glam::vec2;
+
+fn from_fen(fen: &str) -> Chess {
+    Chess::new(vec2(0., 0.))
+        .init_from_fen(fen)
+        .unwrap_or_else(|| panic!("{fen} should be a legal position"))
+}
+
+// the number of move sequences of the given length
+fn perft(chess: &Chess, depth: u32) -> u64 {
+    // the last moves are counted without playing them, which skips most of the work
+    if depth == 1 {
+        return chess.get_legal_moves().len() as u64;
+    }
+
+    chess
+        .get_legal_moves()
+        .iter()
+        .map(|legal_move| {
+            let after = chess
+                .simulate_move(legal_move)
+                .unwrap_or_else(|err| panic!("{err} in {}", chess.get_fen()));
+
+            perft(&after, depth - 1)
+        })
+        .sum()
+}
+
+#[test]
+fn start_position() {
+    let chess = Chess::new(vec2(0., 0.)).init();
+
+    assert_eq!([1, 2, 3, 4].map(|depth| perft(&chess, depth)), [20, 400, 8902, 197281]);
+}
+
+#[test]
+fn kiwipete() {
+    // castling both ways, en passant and pins all show up in the first few plies
+    let chess = from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+
+    assert_eq!([1, 2, 3].map(|depth| perft(&chess, depth)), [48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+    // en passant captures that would uncover a check along the rank
+    let chess = from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1");
+
+    assert_eq!(
+        [1, 2, 3, 4, 5].map(|depth| perft(&chess, depth)),
+        [14, 191, 2812, 43238, 674624]
+    );
+}
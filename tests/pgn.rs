@@ -0,0 +1,122 @@
+// reading games: the movetext tokenizer, san moves and looking up positions by move number
+
+use chess::{
+    pgn::{self, Replay},
+    Chess,
+};
+use ggez::glam::vec2;
+
+fn from_fen(fen: &str) -> Chess {
+    Chess::new(vec2(0., 0.))
+        .init_from_fen(fen)
+        .unwrap_or_else(|| panic!("{fen} should be a legal position"))
+}
+
+// the move a san token describes written as origin, destination and promotion, e.g. "e7e8n"
+fn parse(chess: &Chess, san: &str) -> Option<String> {
+    let (san_move, promote_to) = pgn::parse_san(chess, san)?;
+    let promote_to = promote_to.map_or(String::new(), |piece_type| piece_type.to_string());
+
+    Some(format!("{}{}{promote_to}", san_move.from, san_move.to))
+}
+
+#[test]
+fn moves_skip_everything_but_san() {
+    let pgn = r#"[Event "Casual game"]
+[White "Someone, with spaces"]
+
+1. e4 {best by test} e5 2.Nf3 (2. f4 exf4 (2... d5) 3. Nf3) Nc6 $1
+3...a6 ; the rest of the line is a comment
+4. Ba4 0-0 5. O-O-O 1-0 6. d4"#;
+
+    assert_eq!(
+        pgn::get_moves(pgn),
+        ["e4", "e5", "Nf3", "Nc6", "a6", "Ba4", "0-0", "O-O-O"]
+    );
+}
+
+#[test]
+fn moves_keep_castling_with_zeros_after_a_move_number() {
+    assert_eq!(
+        pgn::get_moves("12.0-0 Nf6 13. Kh1 13...0-0-0 14 Qd2 *"),
+        ["0-0", "Nf6", "Kh1", "0-0-0", "Qd2"]
+    );
+}
+
+#[test]
+fn moves_survive_unmatched_closers() {
+    // the stray closers don't keep the later comment and variation from being skipped
+    assert_eq!(
+        pgn::get_moves("1. e4 } e5 ) 2. Nf3 {a comment} Nc6 (2... d6) 3. Bb5 *"),
+        ["e4", "e5", "Nf3", "Nc6", "Bb5"]
+    );
+}
+
+#[test]
+fn san_names_pieces_captures_and_castling() {
+    let mut chess = Chess::new(vec2(0., 0.)).init();
+
+    assert_eq!(parse(&chess, "e4"), Some("e2e4".to_string()));
+    assert_eq!(parse(&chess, "Nf3"), Some("g1f3".to_string()));
+    // no piece of the side to move can get there
+    assert_eq!(parse(&chess, "e5"), None);
+    assert_eq!(parse(&chess, "Qh5"), None);
+
+    chess = from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(parse(&chess, "exd5"), Some("e4d5".to_string()));
+
+    chess = from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_eq!(parse(&chess, "O-O"), Some("e1g1".to_string()));
+    assert_eq!(parse(&chess, "0-0-0+"), Some("e1c1".to_string()));
+}
+
+#[test]
+fn san_tells_apart_pieces_going_to_the_same_cell() {
+    let chess = from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1");
+
+    assert_eq!(parse(&chess, "Nd2"), None);
+    assert_eq!(parse(&chess, "Nbd2"), Some("b1d2".to_string()));
+    assert_eq!(parse(&chess, "Nf3d2"), Some("f3d2".to_string()));
+}
+
+#[test]
+fn san_reads_promotions() {
+    let chess = from_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1");
+
+    assert_eq!(parse(&chess, "e8=N"), Some("e7e8n".to_string()));
+    assert_eq!(parse(&chess, "e8Q+"), Some("e7e8q".to_string()));
+}
+
+#[test]
+fn positions_are_found_by_move_number() {
+    let replay = Replay::from_pgn("1. e4 e5 2. Nf3 Nc6 *").expect("the game should replay");
+
+    let fen_after = |move_number| replay.get_position_after(move_number).map(Chess::get_fen);
+    let fen_of = |ply: usize| Some(replay.positions[ply].get_fen());
+
+    assert_eq!(fen_after("1"), fen_of(1));
+    assert_eq!(fen_after("1..."), fen_of(2));
+    assert_eq!(fen_after("2"), fen_of(3));
+    assert_eq!(fen_after("2..."), fen_of(4));
+    assert_eq!(fen_after("3"), None);
+    assert_eq!(fen_after("0"), None);
+}
+
+#[test]
+fn positions_are_found_in_a_game_starting_with_black() {
+    let pgn = r#"[FEN "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 5"]
+
+5... e5 6. Nf3 Nc6 7. Bb5 *"#;
+
+    let replay = Replay::from_pgn(pgn).expect("the game should replay");
+
+    let fen_after = |move_number| replay.get_position_after(move_number).map(Chess::get_fen);
+    let fen_of = |ply: usize| Some(replay.positions[ply].get_fen());
+
+    assert_eq!(fen_after("5"), None);
+    assert_eq!(fen_after("5..."), fen_of(1));
+    assert_eq!(fen_after("6"), fen_of(2));
+    assert_eq!(fen_after("6..."), fen_of(3));
+    assert_eq!(fen_after("7"), fen_of(4));
+    assert_eq!(fen_after("7..."), None);
+}
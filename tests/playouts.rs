@@ -0,0 +1,136 @@
+// random legal playouts from the starting position,
+// checking invariants that must hold after every move
+// and that simulating a move and writing the position as FEN lose nothing
+
+use chess::{
+    piece::{PieceColor, PieceType},
+    Chess, Square,
+};
+use ggez::glam::vec2;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+const GAMES: u64 = 24;
+const MAX_PLIES: usize = 120;
+
+fn count_kings(chess: &Chess, color: PieceColor) -> usize {
+    Square::all()
+        .filter(|&square| {
+            chess.get_board()[square].is_some_and(|piece| {
+                piece.get_color() == color && piece.get_piece_type() == PieceType::King
+            })
+        })
+        .count()
+}
+
+fn check_invariants(chess: &Chess) {
+    for color in [PieceColor::White, PieceColor::Black] {
+        assert_eq!(
+            count_kings(chess, color),
+            1,
+            "{} should have exactly one king",
+            color.get_name()
+        );
+    }
+
+    // pawns promote on the last rank and never go back to their own
+    for square in Square::all() {
+        let Some(piece) = chess.get_board()[square] else {
+            continue;
+        };
+
+        if piece.get_piece_type() == PieceType::Pawn {
+            assert!(
+                square.rank() != 0 && square.rank() != 7,
+                "a pawn is left on {square}"
+            );
+        }
+    }
+
+    let mover = chess.get_turn_color();
+
+    for legal_move in chess.get_legal_moves() {
+        let piece = chess.get_board()[legal_move.from]
+            .expect("the origin of a legal move should contain a piece");
+
+        assert!(
+            piece.get_color() == mover,
+            "{} moves a piece of the opponent",
+            legal_move.from
+        );
+
+        assert!(
+            !chess.get_board()[legal_move.to].is_some_and(|target| target.get_color() == mover),
+            "{} to {} captures an own piece",
+            legal_move.from,
+            legal_move.to,
+        );
+    }
+}
+
+// the position read back from its FEN is the same game: same FEN, same legal moves
+fn check_fen_round_trip(chess: &Chess, seed: u64) {
+    let fen = chess.get_fen();
+
+    let restored = Chess::new(vec2(0., 0.))
+        .init_from_fen(&fen)
+        .unwrap_or_else(|| panic!("seed {seed}: {fen} can't be read back"));
+
+    assert_eq!(restored.get_fen(), fen, "seed {seed}: the FEN changed on the round trip");
+    assert!(
+        restored.get_legal_moves() == chess.get_legal_moves(),
+        "seed {seed}: {fen} has other legal moves after the round trip"
+    );
+}
+
+fn play_random_game(seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut chess = Chess::new(vec2(0., 0.)).init();
+
+    for _ in 0..MAX_PLIES {
+        check_invariants(&chess);
+        check_fen_round_trip(&chess, seed);
+
+        let Some(legal_move) = chess.get_legal_moves().choose(&mut rng).copied() else {
+            break;
+        };
+
+        let board_before = *chess.get_board();
+        let fen_before = chess.get_fen();
+        let mover = chess.get_turn_color();
+
+        let after = chess
+            .simulate_move(&legal_move)
+            .unwrap_or_else(|err| panic!("seed {seed}: a legal move cannot be simulated: {err}"));
+
+        // simulating works on a copy and leaves the game untouched
+        assert!(
+            *chess.get_board() == board_before,
+            "seed {seed}: simulate_move changed the game"
+        );
+        assert_eq!(
+            chess.get_fen(),
+            fen_before,
+            "seed {seed}: simulate_move changed the game"
+        );
+
+        assert!(
+            after.get_turn_color() == mover.get_enemy_color(),
+            "seed {seed}: turn did not pass"
+        );
+        assert!(
+            !after.is_in_check(mover),
+            "seed {seed}: {} to {} leaves the king in check",
+            legal_move.from,
+            legal_move.to,
+        );
+
+        chess = after;
+    }
+}
+
+#[test]
+fn random_playouts_keep_invariants() {
+    for seed in 0..GAMES {
+        play_random_game(seed);
+    }
+}